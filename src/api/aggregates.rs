@@ -4,13 +4,17 @@
 use chrono::serde::ts_milliseconds::deserialize as datetime_from_timestamp;
 use chrono::Date;
 use chrono::DateTime;
+use chrono::NaiveDate;
 use chrono::Utc;
 
 use num_decimal::Num;
 
 use serde::Deserialize;
 
+use url::form_urlencoded::Serializer;
+
 use crate::api::response::Response;
+use crate::endpoint::finish_query;
 use crate::Str;
 
 
@@ -68,10 +72,40 @@ pub struct AggregateReq {
 }
 
 
+/// A GET request to be made to the
+/// `/v2/aggs/grouped/locale/us/market/stocks/<date>` endpoint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroupedReq {
+  /// The date to request daily aggregates for.
+  pub date: NaiveDate,
+  /// Whether to adjust the aggregates for splits.
+  pub adjusted: bool,
+  /// Whether to include OTC securities.
+  pub include_otc: bool,
+}
+
+
+/// A GET request to be made to the `/v2/aggs/ticker/<symbol>/prev`
+/// endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreviousReq {
+  /// The ticker symbol to request the previous day's aggregate for.
+  pub symbol: String,
+  /// Whether to adjust the aggregate for splits.
+  pub adjusted: bool,
+}
+
+
 /// A ticker as returned by the
 /// `/v2/aggs/ticker/<symbol>/range/1/<span>/<start>/<end>` endpoint.
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct Aggregate {
+  /// The ticker symbol the aggregate belongs to.
+  ///
+  /// This field is only reported by the `Grouped` and `Previous`
+  /// endpoints.
+  #[serde(rename = "T", default)]
+  pub symbol: Option<String>,
   /// The aggregate's timestamp.
   #[serde(rename = "t", deserialize_with = "datetime_from_timestamp")]
   pub timestamp: DateTime<Utc>,
@@ -120,6 +154,59 @@ Endpoint! {
 }
 
 
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v2/aggs/grouped/locale/us/market/stocks/<date>` endpoint.
+  pub Grouped(GroupedReq),
+  Ok => GetResponse, [
+    /// The daily aggregates were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GroupedError, []
+
+  fn path(input: &Self::Input) -> Str {
+    format!(
+      "/v2/aggs/grouped/locale/us/market/stocks/{}",
+      input.date.format("%Y-%m-%d"),
+    ).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    query.append_pair("adjusted", &input.adjusted.to_string());
+    if input.include_otc {
+      query.append_pair("include_otc", "true");
+    }
+    Ok(finish_query(query))
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v2/aggs/ticker/<symbol>/prev` endpoint.
+  ///
+  /// The endpoint reports the daily aggregate of the previous trading
+  /// day.
+  pub Previous(PreviousReq),
+  Ok => GetResponse, [
+    /// The previous day's aggregate was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => PreviousError, []
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v2/aggs/ticker/{}/prev", input.symbol).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    query.append_pair("adjusted", &input.adjusted.to_string());
+    Ok(finish_query(query))
+  }
+}
+
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -128,9 +215,10 @@ mod tests {
   use std::str::FromStr as _;
 
   use chrono::Duration;
-  use chrono::NaiveDate;
   use chrono::TimeZone as _;
 
+  use http_endpoint::Endpoint as _;
+
   use serde_json::from_str as from_json;
 
   #[cfg(not(target_arch = "wasm32"))]
@@ -167,6 +255,106 @@ mod tests {
     assert_eq!(aggregate.close_price, Num::new(10374, 100));
     assert_eq!(aggregate.high_price, Num::new(10382, 100));
     assert_eq!(aggregate.low_price, Num::new(10265, 100));
+    assert_eq!(aggregate.symbol, None);
+  }
+
+  /// Check that we emit the expected path and query for grouped
+  /// aggregates.
+  #[test]
+  fn encode_grouped_request() {
+    let request = GroupedReq {
+      date: NaiveDate::from_str("2021-11-01").unwrap(),
+      adjusted: true,
+      include_otc: false,
+    };
+    assert_eq!(
+      Grouped::path(&request),
+      "/v2/aggs/grouped/locale/us/market/stocks/2021-11-01"
+    );
+    assert_eq!(Grouped::query(&request).unwrap().unwrap(), "adjusted=true");
+
+    let request = GroupedReq {
+      adjusted: false,
+      include_otc: true,
+      ..request
+    };
+    assert_eq!(
+      Grouped::query(&request).unwrap().unwrap(),
+      "adjusted=false&include_otc=true"
+    );
+  }
+
+  /// Check that we emit the expected path and query for the previous
+  /// day's aggregate.
+  #[test]
+  fn encode_previous_request() {
+    let request = PreviousReq {
+      symbol: "AAPL".into(),
+      adjusted: false,
+    };
+    assert_eq!(Previous::path(&request), "/v2/aggs/ticker/AAPL/prev");
+    assert_eq!(Previous::query(&request).unwrap().unwrap(), "adjusted=false");
+  }
+
+  /// Make sure that we can deserialize the previous day's aggregate.
+  #[test]
+  fn deserialize_previous_response() {
+    let response = r#"{
+  "adjusted": true,
+  "queryCount": 1,
+  "request_id": "6a7e466379af0a71039d60cc78e72282",
+  "results": [
+    {"T": "AAPL", "c": 115.97, "h": 117.59, "l": 114.13, "o": 116.97, "t": 1605042000000, "v": 131704427, "vw": 116.3058}
+  ],
+  "resultsCount": 1,
+  "status": "OK",
+  "ticker": "AAPL"
+}"#;
+
+    let aggregates = from_json::<GetResponse>(response)
+      .unwrap()
+      .into_result()
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(aggregates.len(), 1);
+    assert_eq!(aggregates[0].symbol.as_deref(), Some("AAPL"));
+    assert_eq!(aggregates[0].close_price, Num::new(11597, 100));
+    assert_eq!(
+      aggregates[0].timestamp,
+      DateTime::parse_from_rfc3339("2020-11-10T16:00:00-05:00").unwrap()
+    );
+  }
+
+  /// Make sure that we can deserialize grouped daily aggregates.
+  #[test]
+  fn deserialize_grouped_response() {
+    let response = r#"{
+  "adjusted": true,
+  "queryCount": 2,
+  "request_id": "eae3ded2d6d43f978125b7a8a609fad9",
+  "results": [
+    {"T": "KIMpL", "v": 4369, "vw": 26.0407, "o": 26.07, "c": 25.9102, "h": 26.25, "l": 25.91, "t": 1602705600000, "n": 74},
+    {"T": "TANH", "v": 25933.6, "vw": 3.493, "o": 3.4, "c": 3.4, "h": 3.54, "l": 3.37, "t": 1602705600000, "n": 169}
+  ],
+  "resultsCount": 2,
+  "status": "OK"
+}"#;
+
+    let aggregates = from_json::<GetResponse>(response)
+      .unwrap()
+      .into_result()
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(aggregates.len(), 2);
+    assert_eq!(aggregates[0].symbol.as_deref(), Some("KIMpL"));
+    assert_eq!(aggregates[0].close_price, Num::new(259_102, 10000));
+    assert_eq!(aggregates[1].symbol.as_deref(), Some("TANH"));
+    assert_eq!(
+      aggregates[1].timestamp,
+      DateTime::parse_from_rfc3339("2020-10-14T16:00:00-04:00").unwrap()
+    );
   }
 
   #[test]
@@ -408,6 +596,52 @@ mod tests {
     );
   }
 
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_grouped_aggregates() {
+    let client = Client::from_env().unwrap();
+    let request = GroupedReq {
+      date: NaiveDate::from_str("2021-11-01").unwrap(),
+      adjusted: true,
+      include_otc: false,
+    };
+
+    let aggregates = client
+      .issue::<Grouped>(request)
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap()
+      .unwrap();
+
+    assert!(aggregates.len() > 1000, "{}", aggregates.len());
+    assert!(aggregates
+      .iter()
+      .any(|aggregate| aggregate.symbol.as_deref() == Some("AAPL")));
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_previous_aggregate() {
+    let client = Client::from_env().unwrap();
+    let request = PreviousReq {
+      symbol: "AAPL".into(),
+      adjusted: true,
+    };
+
+    let aggregates = client
+      .issue::<Previous>(request)
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(aggregates.len(), 1);
+    assert_eq!(aggregates[0].symbol.as_deref(), Some("AAPL"));
+    assert!(aggregates[0].timestamp < Utc::now());
+  }
+
   /// Test that we can properly handle a response containing potentially
   /// "delayed" data.
   #[cfg(not(target_arch = "wasm32"))]
@@ -0,0 +1,515 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// The asset class a condition applies to.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+pub enum AssetClass {
+  /// Stocks.
+  #[serde(rename = "stocks")]
+  Stocks,
+  /// Options.
+  #[serde(rename = "options")]
+  Options,
+  /// Crypto currencies.
+  #[serde(rename = "crypto")]
+  Crypto,
+  /// Foreign exchange.
+  #[serde(rename = "fx")]
+  ForeignExchange,
+}
+
+impl AsRef<str> for AssetClass {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      AssetClass::Stocks => "stocks",
+      AssetClass::Options => "options",
+      AssetClass::Crypto => "crypto",
+      AssetClass::ForeignExchange => "fx",
+    }
+  }
+}
+
+
+/// The type of data a condition applies to.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum DataType {
+  /// Trades.
+  #[serde(rename = "trade")]
+  Trade,
+  /// Best bid and offer quotes of a single exchange.
+  #[serde(rename = "bbo")]
+  Bbo,
+  /// National best bid and offer quotes.
+  #[serde(rename = "nbbo")]
+  Nbbo,
+}
+
+impl AsRef<str> for DataType {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      DataType::Trade => "trade",
+      DataType::Bbo => "bbo",
+      DataType::Nbbo => "nbbo",
+    }
+  }
+}
+
+
+/// The field to sort conditions by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the condition's ID.
+  Id,
+  /// Sort by the condition's name.
+  Name,
+  /// Sort by the condition's type.
+  Type,
+  /// Sort by the condition's asset class.
+  AssetClass,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Id => "id",
+      Sort::Name => "name",
+      Sort::Type => "type",
+      Sort::AssetClass => "asset_class",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v3/reference/conditions`
+/// endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConditionReq {
+  /// Only report conditions of this asset class.
+  pub asset_class: Option<AssetClass>,
+  /// Only report conditions applying to this data type.
+  pub data_type: Option<DataType>,
+  /// Only report the condition with this ID.
+  pub id: Option<u64>,
+  /// The order in which to report conditions.
+  pub order: Option<Order>,
+  /// The field to sort conditions by.
+  pub sort: Option<Sort>,
+  /// The maximum number of conditions to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// Rules describing which parts of aggregated data a trade with a
+/// certain condition updates.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct UpdateRule {
+  /// Whether the trade updates the high and low prices.
+  #[serde(rename = "updates_high_low")]
+  pub updates_high_low: bool,
+  /// Whether the trade updates the open and close (last) prices.
+  #[serde(rename = "updates_open_close")]
+  pub updates_open_close: bool,
+  /// Whether the trade updates the volume.
+  #[serde(rename = "updates_volume")]
+  pub updates_volume: bool,
+}
+
+
+/// The update rules of a condition.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct UpdateRules {
+  /// The rules applying to consolidated data, i.e., data across all
+  /// exchanges.
+  #[serde(rename = "consolidated")]
+  pub consolidated: UpdateRule,
+  /// The rules applying to data of the exchange the trade occurred
+  /// on.
+  #[serde(rename = "market_center")]
+  pub market_center: UpdateRule,
+}
+
+
+/// A condition as returned by the `/v3/reference/conditions`
+/// endpoint.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Condition {
+  /// The condition's ID, as used in trades and quotes.
+  #[serde(rename = "id")]
+  pub id: u64,
+  /// The condition's name.
+  #[serde(rename = "name")]
+  pub name: String,
+  /// A description of the condition.
+  #[serde(rename = "description")]
+  pub description: Option<String>,
+  /// The type of the condition, e.g., `sale_condition`.
+  #[serde(rename = "type")]
+  pub type_: String,
+  /// The asset class the condition applies to.
+  #[serde(rename = "asset_class")]
+  pub asset_class: AssetClass,
+  /// The data types the condition applies to.
+  #[serde(rename = "data_types", default)]
+  pub data_types: Vec<DataType>,
+  /// Whether the condition is no longer in use.
+  #[serde(rename = "legacy", default)]
+  pub legacy: bool,
+  /// A mapping from SIP to the condition's code used by it.
+  #[serde(rename = "sip_mapping", default)]
+  pub sip_mapping: BTreeMap<String, String>,
+  /// The rules describing how trades with this condition update
+  /// aggregated data.
+  #[serde(rename = "update_rules")]
+  pub update_rules: Option<UpdateRules>,
+}
+
+
+/// A lookup table for trade conditions.
+///
+/// The table can be used to check how trades with a given set of
+/// conditions affect aggregated data, as described by the consolidated
+/// update rules. Because condition IDs are only unique within an asset
+/// class, conditions are looked up by asset class and ID. Conditions
+/// not applying to trades are ignored when creating the table. A
+/// condition not found in the table, as well as one without update
+/// rules, is assumed to update everything.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConditionTable {
+  /// The trade conditions, indexed by their asset class and ID.
+  conditions: BTreeMap<(AssetClass, u64), Condition>,
+}
+
+impl ConditionTable {
+  /// Look up the trade condition of the given asset class with the
+  /// given ID.
+  pub fn get(&self, asset_class: AssetClass, id: u64) -> Option<&Condition> {
+    self.conditions.get(&(asset_class, id))
+  }
+
+  /// Check whether all of the given conditions satisfy a predicate.
+  fn all<F>(&self, asset_class: AssetClass, conditions: &[u64], f: F) -> bool
+  where
+    F: Fn(&UpdateRule) -> bool,
+  {
+    conditions.iter().all(|id| {
+      self
+        .get(asset_class, *id)
+        .and_then(|condition| condition.update_rules.as_ref())
+        .map(|rules| f(&rules.consolidated))
+        .unwrap_or(true)
+    })
+  }
+
+  /// Check whether a trade of the given asset class with the given
+  /// conditions updates the high and low prices.
+  pub fn updates_high_low(&self, asset_class: AssetClass, conditions: &[u64]) -> bool {
+    self.all(asset_class, conditions, |rule| rule.updates_high_low)
+  }
+
+  /// Check whether a trade of the given asset class with the given
+  /// conditions updates the last price.
+  pub fn updates_last(&self, asset_class: AssetClass, conditions: &[u64]) -> bool {
+    self.all(asset_class, conditions, |rule| rule.updates_open_close)
+  }
+
+  /// Check whether a trade of the given asset class with the given
+  /// conditions updates the volume.
+  pub fn updates_volume(&self, asset_class: AssetClass, conditions: &[u64]) -> bool {
+    self.all(asset_class, conditions, |rule| rule.updates_volume)
+  }
+}
+
+impl FromIterator<Condition> for ConditionTable {
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = Condition>,
+  {
+    let conditions = iter
+      .into_iter()
+      .filter(|condition| condition.data_types.contains(&DataType::Trade))
+      .map(|condition| ((condition.asset_class, condition.id), condition))
+      .collect();
+
+    Self { conditions }
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/reference/conditions` endpoint.
+  pub List(ConditionReq),
+  Ok => Page<Condition>, [
+    /// The conditions were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v3/reference/conditions".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      if let Some(asset_class) = &input.asset_class {
+        query.append_pair("asset_class", asset_class.as_ref());
+      }
+      if let Some(data_type) = &input.data_type {
+        query.append_pair("data_type", data_type.as_ref());
+      }
+      if let Some(id) = &input.id {
+        query.append_pair("id", &id.to_string());
+      }
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Condition;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  const CONDITIONS: &str = r#"{
+  "count": 3,
+  "request_id": "4599a4e2ba5e19e2e732f711e97b0d84",
+  "results": [
+    {
+      "asset_class": "stocks",
+      "data_types": ["trade"],
+      "id": 0,
+      "name": "Regular Trade",
+      "sip_mapping": {"CTA": "@", "UTP": "@"},
+      "type": "regular",
+      "update_rules": {
+        "consolidated": {"updates_high_low": true, "updates_open_close": true, "updates_volume": true},
+        "market_center": {"updates_high_low": true, "updates_open_close": true, "updates_volume": true}
+      }
+    },
+    {
+      "asset_class": "stocks",
+      "data_types": ["trade"],
+      "description": "A trade for less than a round lot.",
+      "id": 37,
+      "name": "Odd Lot Trade",
+      "sip_mapping": {"CTA": "I", "UTP": "I"},
+      "type": "sale_condition",
+      "update_rules": {
+        "consolidated": {"updates_high_low": false, "updates_open_close": false, "updates_volume": true},
+        "market_center": {"updates_high_low": false, "updates_open_close": false, "updates_volume": true}
+      }
+    },
+    {
+      "asset_class": "stocks",
+      "data_types": ["bbo", "nbbo"],
+      "id": 1,
+      "legacy": false,
+      "name": "Regular, Two-Sided Open",
+      "sip_mapping": {"CTA": "R", "UTP": "R"},
+      "type": "quote_condition"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = ConditionReq {
+      asset_class: Some(AssetClass::Stocks),
+      data_type: Some(DataType::Trade),
+      order: Some(Order::Ascending),
+      sort: Some(Sort::Id),
+      limit: Some(1000),
+      ..Default::default()
+    };
+
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "asset_class=stocks&data_type=trade&order=asc&sort=id&limit=1000"
+    );
+  }
+
+  /// Make sure that we can deserialize a page of conditions.
+  #[test]
+  fn deserialize_conditions() {
+    let page = from_json::<Page<Condition>>(CONDITIONS).unwrap();
+    assert_eq!(page.cursor, None);
+    assert_eq!(page.results.len(), 3);
+
+    let condition = &page.results[1];
+    assert_eq!(condition.id, 37);
+    assert_eq!(condition.name, "Odd Lot Trade");
+    assert_eq!(condition.type_, "sale_condition");
+    assert_eq!(condition.asset_class, AssetClass::Stocks);
+    assert_eq!(condition.data_types, vec![DataType::Trade]);
+    assert!(!condition.legacy);
+    assert_eq!(condition.sip_mapping["CTA"], "I");
+
+    let rules = condition.update_rules.unwrap();
+    assert!(!rules.consolidated.updates_high_low);
+    assert!(!rules.consolidated.updates_open_close);
+    assert!(rules.consolidated.updates_volume);
+
+    let condition = &page.results[2];
+    assert_eq!(condition.data_types, vec![DataType::Bbo, DataType::Nbbo]);
+    assert_eq!(condition.update_rules, None);
+  }
+
+  /// Check that the condition table reports the expected update
+  /// behavior.
+  #[test]
+  fn condition_table() {
+    let page = from_json::<Page<Condition>>(CONDITIONS).unwrap();
+    let table = page.results.into_iter().collect::<ConditionTable>();
+    let stocks = AssetClass::Stocks;
+
+    assert_eq!(table.get(stocks, 37).unwrap().name, "Odd Lot Trade");
+    // Quote conditions are not part of the table.
+    assert_eq!(table.get(stocks, 1), None);
+
+    assert!(table.updates_high_low(stocks, &[]));
+    assert!(table.updates_high_low(stocks, &[0]));
+    assert!(table.updates_last(stocks, &[0]));
+    assert!(table.updates_volume(stocks, &[0]));
+
+    assert!(!table.updates_high_low(stocks, &[0, 37]));
+    assert!(!table.updates_last(stocks, &[37]));
+    assert!(table.updates_volume(stocks, &[37]));
+
+    // Unknown conditions are assumed to update everything.
+    assert!(table.updates_high_low(stocks, &[1]));
+    assert!(table.updates_last(AssetClass::Options, &[37]));
+  }
+
+  /// Check that conditions of different asset classes sharing an ID do
+  /// not overwrite each other.
+  #[test]
+  fn condition_table_asset_classes() {
+    let response = r#"{
+  "results": [
+    {
+      "asset_class": "stocks",
+      "data_types": ["trade"],
+      "id": 37,
+      "name": "Odd Lot Trade",
+      "type": "sale_condition",
+      "update_rules": {
+        "consolidated": {"updates_high_low": false, "updates_open_close": false, "updates_volume": true},
+        "market_center": {"updates_high_low": false, "updates_open_close": false, "updates_volume": true}
+      }
+    },
+    {
+      "asset_class": "options",
+      "data_types": ["trade"],
+      "id": 37,
+      "name": "Multi Leg Auto Electronic Trade",
+      "type": "sale_condition",
+      "update_rules": {
+        "consolidated": {"updates_high_low": true, "updates_open_close": true, "updates_volume": true},
+        "market_center": {"updates_high_low": true, "updates_open_close": true, "updates_volume": true}
+      }
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Condition>>(response).unwrap();
+    let table = page.results.into_iter().collect::<ConditionTable>();
+
+    assert_eq!(table.get(AssetClass::Stocks, 37).unwrap().name, "Odd Lot Trade");
+    assert_eq!(
+      table.get(AssetClass::Options, 37).unwrap().name,
+      "Multi Leg Auto Electronic Trade"
+    );
+    assert_eq!(table.get(AssetClass::Crypto, 37), None);
+
+    assert!(!table.updates_last(AssetClass::Stocks, &[37]));
+    assert!(table.updates_last(AssetClass::Options, &[37]));
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_trade_conditions() {
+    let client = Client::from_env().unwrap();
+    let request = ConditionReq {
+      asset_class: Some(AssetClass::Stocks),
+      data_type: Some(DataType::Trade),
+      limit: Some(1000),
+      ..Default::default()
+    };
+
+    let table = client
+      .pages::<List>(request)
+      .try_concat()
+      .await
+      .unwrap()
+      .into_iter()
+      .collect::<ConditionTable>();
+
+    assert_eq!(table.get(AssetClass::Stocks, 37).unwrap().name, "Odd Lot Trade");
+    assert!(!table.updates_last(AssetClass::Stocks, &[37]));
+  }
+}
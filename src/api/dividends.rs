@@ -0,0 +1,330 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::NaiveDate;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::filter::append_filters;
+use crate::api::filter::Filter;
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// The type of a dividend.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum DividendType {
+  /// A regular cash dividend, paid consistently.
+  #[serde(rename = "CD")]
+  Consistent,
+  /// A special cash dividend, paid infrequently or not at all.
+  #[serde(rename = "SC")]
+  Special,
+  /// A long-term capital gain distribution.
+  #[serde(rename = "LT")]
+  LongTermGain,
+  /// A short-term capital gain distribution.
+  #[serde(rename = "ST")]
+  ShortTermGain,
+}
+
+impl AsRef<str> for DividendType {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      DividendType::Consistent => "CD",
+      DividendType::Special => "SC",
+      DividendType::LongTermGain => "LT",
+      DividendType::ShortTermGain => "ST",
+    }
+  }
+}
+
+
+/// The field to sort dividends by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the ticker symbol.
+  Ticker,
+  /// Sort by the ex-dividend date.
+  ExDividendDate,
+  /// Sort by the record date.
+  RecordDate,
+  /// Sort by the pay date.
+  PayDate,
+  /// Sort by the declaration date.
+  DeclarationDate,
+  /// Sort by the cash amount.
+  CashAmount,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Ticker => "ticker",
+      Sort::ExDividendDate => "ex_dividend_date",
+      Sort::RecordDate => "record_date",
+      Sort::PayDate => "pay_date",
+      Sort::DeclarationDate => "declaration_date",
+      Sort::CashAmount => "cash_amount",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v3/reference/dividends` endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DividendReq {
+  /// Only report dividends of this ticker.
+  pub ticker: Option<String>,
+  /// Filters on the ex-dividend date.
+  pub ex_dividend_date: Vec<Filter<NaiveDate>>,
+  /// Filters on the record date.
+  pub record_date: Vec<Filter<NaiveDate>>,
+  /// Filters on the pay date.
+  pub pay_date: Vec<Filter<NaiveDate>>,
+  /// Only report dividends paid this many times per year.
+  pub frequency: Option<u8>,
+  /// Filters on the cash amount.
+  pub cash_amount: Vec<Filter<Num>>,
+  /// Only report dividends of this type.
+  pub dividend_type: Option<DividendType>,
+  /// The order in which to report dividends.
+  pub order: Option<Order>,
+  /// The field to sort dividends by.
+  pub sort: Option<Sort>,
+  /// The maximum number of dividends to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// A dividend as returned by the `/v3/reference/dividends` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Dividend {
+  /// The dividend's ID.
+  #[serde(rename = "id")]
+  pub id: Option<String>,
+  /// The ticker symbol.
+  #[serde(rename = "ticker")]
+  pub ticker: String,
+  /// The cash amount paid per share.
+  #[serde(rename = "cash_amount")]
+  pub cash_amount: Num,
+  /// The currency the cash amount is in.
+  #[serde(rename = "currency")]
+  pub currency: Option<String>,
+  /// The date the dividend was announced.
+  #[serde(rename = "declaration_date")]
+  pub declaration_date: Option<NaiveDate>,
+  /// The first date on which the stock trades without the dividend.
+  #[serde(rename = "ex_dividend_date")]
+  pub ex_dividend_date: NaiveDate,
+  /// The date by which one has to be a shareholder to receive the
+  /// dividend.
+  #[serde(rename = "record_date")]
+  pub record_date: Option<NaiveDate>,
+  /// The date on which the dividend is paid.
+  #[serde(rename = "pay_date")]
+  pub pay_date: Option<NaiveDate>,
+  /// The number of times per year the dividend is paid.
+  ///
+  /// A value of `0` indicates a one-time dividend.
+  #[serde(rename = "frequency")]
+  pub frequency: Option<u8>,
+  /// The type of the dividend.
+  #[serde(rename = "dividend_type")]
+  pub dividend_type: DividendType,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/reference/dividends` endpoint.
+  pub List(DividendReq),
+  Ok => Page<Dividend>, [
+    /// The dividends were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v3/reference/dividends".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      if let Some(ticker) = &input.ticker {
+        query.append_pair("ticker", ticker);
+      }
+      append_filters(&mut query, "ex_dividend_date", &input.ex_dividend_date);
+      append_filters(&mut query, "record_date", &input.record_date);
+      append_filters(&mut query, "pay_date", &input.pay_date);
+      if let Some(frequency) = &input.frequency {
+        query.append_pair("frequency", &frequency.to_string());
+      }
+      append_filters(&mut query, "cash_amount", &input.cash_amount);
+      if let Some(dividend_type) = &input.dividend_type {
+        query.append_pair("dividend_type", dividend_type.as_ref());
+      }
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Dividend;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = DividendReq {
+      ticker: Some("AAPL".into()),
+      ex_dividend_date: vec![
+        Filter::GreaterThanOrEqual(NaiveDate::from_str("2021-01-01").unwrap()),
+        Filter::LessThan(NaiveDate::from_str("2022-01-01").unwrap()),
+      ],
+      frequency: Some(4),
+      cash_amount: vec![Filter::GreaterThan(Num::new(1, 10))],
+      dividend_type: Some(DividendType::Consistent),
+      order: Some(Order::Ascending),
+      sort: Some(Sort::ExDividendDate),
+      limit: Some(50),
+      ..Default::default()
+    };
+
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "ticker=AAPL&ex_dividend_date.gte=2021-01-01&ex_dividend_date.lt=2022-01-01&frequency=4&cash_amount.gt=0.1&dividend_type=CD&order=asc&sort=ex_dividend_date&limit=50"
+    );
+  }
+
+  /// Make sure that we can deserialize a page of dividends.
+  #[test]
+  fn deserialize_dividends() {
+    let response = r#"{
+  "next_url": "https://api.polygon.io/v3/reference/dividends?cursor=YXA9MjAyMS0xMS0wNSZhcz0mbGltaXQ9MQ",
+  "request_id": "ae7a5b6e8e7b6e1a3b0d4a0c1b6b7e2a",
+  "results": [
+    {
+      "cash_amount": 0.22,
+      "currency": "USD",
+      "declaration_date": "2021-10-28",
+      "dividend_type": "CD",
+      "ex_dividend_date": "2021-11-05",
+      "frequency": 4,
+      "id": "E8e3c4f794613e9205e2f178a36c53fcc57cdabb55e1988c87b33f9e52e221444",
+      "pay_date": "2021-11-11",
+      "record_date": "2021-11-08",
+      "ticker": "AAPL"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Dividend>>(response).unwrap();
+    assert!(page.cursor.is_some());
+    assert_eq!(page.results.len(), 1);
+
+    let dividend = &page.results[0];
+    assert_eq!(dividend.ticker, "AAPL");
+    assert_eq!(dividend.cash_amount, Num::new(22, 100));
+    assert_eq!(dividend.currency.as_deref(), Some("USD"));
+    assert_eq!(
+      dividend.declaration_date,
+      Some(NaiveDate::from_str("2021-10-28").unwrap())
+    );
+    assert_eq!(
+      dividend.ex_dividend_date,
+      NaiveDate::from_str("2021-11-05").unwrap()
+    );
+    assert_eq!(
+      dividend.record_date,
+      Some(NaiveDate::from_str("2021-11-08").unwrap())
+    );
+    assert_eq!(
+      dividend.pay_date,
+      Some(NaiveDate::from_str("2021-11-11").unwrap())
+    );
+    assert_eq!(dividend.frequency, Some(4));
+    assert_eq!(dividend.dividend_type, DividendType::Consistent);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_dividends() {
+    let client = Client::from_env().unwrap();
+    let request = DividendReq {
+      ticker: Some("AAPL".into()),
+      ex_dividend_date: vec![
+        Filter::GreaterThanOrEqual(NaiveDate::from_str("2021-01-01").unwrap()),
+        Filter::LessThan(NaiveDate::from_str("2022-01-01").unwrap()),
+      ],
+      ..Default::default()
+    };
+
+    let dividends = client
+      .pages::<List>(request)
+      .try_concat()
+      .await
+      .unwrap();
+
+    // Apple paid a quarterly dividend in 2021.
+    assert_eq!(dividends.len(), 4);
+    assert!(dividends.iter().all(|dividend| dividend.ticker == "AAPL"));
+    assert!(dividends.iter().all(|dividend| dividend.frequency == Some(4)));
+  }
+}
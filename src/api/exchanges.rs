@@ -1,47 +1,146 @@
 // Copyright (C) 2020-2022 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
 use serde::Deserialize;
 
+use url::form_urlencoded::Serializer;
+
+use crate::api::conditions::AssetClass;
+use crate::api::response::Response;
+use crate::endpoint::finish_query;
 use crate::Str;
 
 
-/// An exchange as returned by the `/v1/meta/exchanges` endpoint.
+/// The type of an exchange.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Type {
+  /// A regular exchange.
+  #[serde(rename = "exchange")]
+  Exchange,
+  /// A trade reporting facility.
+  #[serde(rename = "TRF")]
+  TradeReportingFacility,
+  /// A securities information processor.
+  #[serde(rename = "SIP")]
+  SecuritiesInformationProcessor,
+  /// Any other type.
+  #[serde(other)]
+  Other,
+}
+
+
+/// A GET request to be made to the `/v3/reference/exchanges` endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExchangeReq {
+  /// Only report exchanges of this asset class.
+  pub asset_class: Option<AssetClass>,
+  /// Only report exchanges of this locale, e.g., `us`.
+  pub locale: Option<String>,
+}
+
+
+/// An exchange as returned by the `/v3/reference/exchanges` endpoint.
 ///
 /// Please note that not all fields available in a request are
 /// represented here.
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct Exchange {
   /// Exchange ID.
+  ///
+  /// This ID is what trades and quotes refer to, e.g., via
+  /// `events::Trade::exchange`.
   #[serde(rename = "id")]
-  pub id: usize,
+  pub id: u64,
   /// The type of exchange.
   #[serde(rename = "type")]
-  pub type_: String,
-  /// The type of market data the exchange provides.
-  #[serde(rename = "market")]
-  pub market: String,
+  pub type_: Type,
+  /// The asset class the exchange is for.
+  #[serde(rename = "asset_class")]
+  pub asset_class: AssetClass,
+  /// The exchange's locale.
+  #[serde(rename = "locale")]
+  pub locale: String,
   /// The exchange's name.
   #[serde(rename = "name")]
   pub name: String,
-  /// The exchange's code.
-  ///
-  /// This field is seemingly only set for exchanges of type `Equities`.
-  pub code: Option<String>,
+  /// The exchange's acronym.
+  #[serde(rename = "acronym")]
+  pub acronym: Option<String>,
+  /// The exchange's market identifier code (MIC).
+  #[serde(rename = "mic")]
+  pub mic: Option<String>,
+  /// The MIC of the entity operating the exchange.
+  #[serde(rename = "operating_mic")]
+  pub operating_mic: Option<String>,
+  /// The ID used by the SIP to refer to the exchange, e.g., `A`.
+  #[serde(rename = "participant_id")]
+  pub participant_id: Option<String>,
+  /// The URL of the exchange's website.
+  #[serde(rename = "url")]
+  pub url: Option<String>,
+}
+
+
+/// A lookup table for exchanges.
+///
+/// The table maps the exchange IDs reported in trades and quotes to
+/// the corresponding exchange. Because IDs are only unique within an
+/// asset class, the table should be created from exchanges of a single
+/// asset class.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExchangeTable {
+  /// The exchanges, indexed by their ID.
+  exchanges: BTreeMap<u64, Exchange>,
+}
+
+impl ExchangeTable {
+  /// Look up the exchange with the given ID.
+  pub fn get(&self, id: u64) -> Option<&Exchange> {
+    self.exchanges.get(&id)
+  }
+}
+
+impl FromIterator<Exchange> for ExchangeTable {
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = Exchange>,
+  {
+    let exchanges = iter
+      .into_iter()
+      .map(|exchange| (exchange.id, exchange))
+      .collect();
+
+    Self { exchanges }
+  }
 }
 
 
 Endpoint! {
-  /// The representation of a GET request to the `/v1/meta/exchanges` endpoint.
-  pub Get(()),
-  Ok => Vec<Exchange>, [
+  /// The representation of a GET request to the
+  /// `/v3/reference/exchanges` endpoint.
+  pub Get(ExchangeReq),
+  Ok => Response<Vec<Exchange>>, [
     /// The exchanges information was retrieved successfully.
     /* 200 */ OK,
   ],
   Err => GetError, []
 
   fn path(_input: &Self::Input) -> Str {
-    "/v1/meta/exchanges".into()
+    "/v3/reference/exchanges".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(asset_class) = &input.asset_class {
+      query.append_pair("asset_class", asset_class.as_ref());
+    }
+    if let Some(locale) = &input.locale {
+      query.append_pair("locale", locale);
+    }
+    Ok(finish_query(query))
   }
 }
 
@@ -50,6 +149,8 @@ Endpoint! {
 mod tests {
   use super::*;
 
+  use http_endpoint::Endpoint as _;
+
   use serde_json::from_str as from_json;
 
   #[cfg(not(target_arch = "wasm32"))]
@@ -59,68 +160,127 @@ mod tests {
   use crate::Client;
 
 
+  /// Check that we emit the expected query parameters.
   #[test]
-  fn parse_reference_exchanges() {
-    let response = r#"[
-  {
-    "id": 1,
-    "type": "exchange",
-    "market": "equities",
-    "mic": "XASE",
-    "name": "NYSE American (AMEX)",
-    "tape": "A"
-  },
-  {
-    "id": 2,
-    "type": "exchange",
-    "market": "equities",
-    "mic": "XBOS",
-    "name": "NASDAQ OMX BX",
-    "tape": "B"
-  },
-  {
-    "id": 15,
-    "type": "exchange",
-    "market": "equities",
-    "mic": "IEXG",
-    "name": "IEX",
-    "tape": "V"
-  },
-  {
-    "id": 16,
-    "type": "TRF",
-    "market": "equities",
-    "mic": "XCBO",
-    "name": "Chicago Board Options Exchange",
-    "tape": "W"
+  fn encode_query() {
+    assert_eq!(Get::query(&ExchangeReq::default()).unwrap(), None);
+
+    let request = ExchangeReq {
+      asset_class: Some(AssetClass::Stocks),
+      locale: Some("us".into()),
+    };
+    let query = Get::query(&request).unwrap().unwrap();
+    assert_eq!(query, "asset_class=stocks&locale=us");
   }
-]"#;
 
-    let exchgs = from_json::<Vec<Exchange>>(response).unwrap();
+  #[test]
+  fn parse_reference_exchanges() {
+    let response = r#"{
+  "count": 4,
+  "request_id": "c784b78622b5a68c932b1f4e0c6a3d9b",
+  "results": [
+    {
+      "acronym": "AMEX",
+      "asset_class": "stocks",
+      "id": 1,
+      "locale": "us",
+      "mic": "XASE",
+      "name": "NYSE American, LLC",
+      "operating_mic": "XNYS",
+      "participant_id": "A",
+      "type": "exchange",
+      "url": "https://www.nyse.com/markets/nyse-american"
+    },
+    {
+      "asset_class": "stocks",
+      "id": 2,
+      "locale": "us",
+      "mic": "XBOS",
+      "name": "Nasdaq OMX BX, Inc.",
+      "operating_mic": "XNAS",
+      "participant_id": "B",
+      "type": "exchange",
+      "url": "https://www.nasdaq.com/solutions/nasdaq-bx-stock-market"
+    },
+    {
+      "acronym": "IEX",
+      "asset_class": "stocks",
+      "id": 15,
+      "locale": "us",
+      "mic": "IEXG",
+      "name": "Investors Exchange",
+      "operating_mic": "IEXG",
+      "participant_id": "V",
+      "type": "exchange",
+      "url": "https://www.iextrading.com"
+    },
+    {
+      "asset_class": "stocks",
+      "id": 201,
+      "locale": "us",
+      "name": "FINRA NYSE TRF",
+      "operating_mic": "FINR",
+      "participant_id": "D",
+      "type": "TRF"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let exchgs = from_json::<Response<Vec<Exchange>>>(response)
+      .unwrap()
+      .into_result()
+      .unwrap();
     assert_eq!(exchgs.len(), 4);
     assert_eq!(exchgs[0].id, 1);
-    assert_eq!(exchgs[0].type_, "exchange");
-    assert_eq!(exchgs[0].market, "equities");
-    assert_eq!(exchgs[0].name, "NYSE American (AMEX)");
+    assert_eq!(exchgs[0].type_, Type::Exchange);
+    assert_eq!(exchgs[0].asset_class, AssetClass::Stocks);
+    assert_eq!(exchgs[0].locale, "us");
+    assert_eq!(exchgs[0].name, "NYSE American, LLC");
+    assert_eq!(exchgs[0].acronym.as_deref(), Some("AMEX"));
+    assert_eq!(exchgs[0].mic.as_deref(), Some("XASE"));
+    assert_eq!(exchgs[0].operating_mic.as_deref(), Some("XNYS"));
+    assert_eq!(exchgs[0].participant_id.as_deref(), Some("A"));
+    assert_eq!(
+      exchgs[0].url.as_deref(),
+      Some("https://www.nyse.com/markets/nyse-american")
+    );
     assert_eq!(exchgs[1].id, 2);
+    assert_eq!(exchgs[1].acronym, None);
     assert_eq!(exchgs[2].id, 15);
-    assert_eq!(exchgs[3].id, 16);
+    assert_eq!(exchgs[3].id, 201);
+    assert_eq!(exchgs[3].type_, Type::TradeReportingFacility);
+    assert_eq!(exchgs[3].mic, None);
+
+    let table = exchgs.into_iter().collect::<ExchangeTable>();
+    assert_eq!(table.get(15).unwrap().name, "Investors Exchange");
+    assert_eq!(table.get(3), None);
   }
 
   #[cfg(not(target_arch = "wasm32"))]
   #[test(tokio::test)]
   async fn request_exchanges() {
     let client = Client::from_env().unwrap();
-    let exchgs = client.issue::<Get>(()).await.unwrap();
+    let request = ExchangeReq {
+      asset_class: Some(AssetClass::Stocks),
+      locale: Some("us".into()),
+    };
+    let exchgs = client
+      .issue::<Get>(request)
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
 
     assert!(!exchgs.is_empty());
-
-    // We are in trouble if NYE cannot be found.
-    let nye = exchgs
+    assert!(exchgs
       .iter()
-      .find(|exchg| exchg.code.as_deref() == Some("NYE"))
-      .unwrap();
-    assert_eq!(nye.type_, "exchange");
-    assert_eq!(nye.market, "equities");
+      .all(|exchg| exchg.asset_class == AssetClass::Stocks));
+
+    // We are in trouble if the NYSE cannot be found.
+    let table = exchgs.into_iter().collect::<ExchangeTable>();
+    let nyse = table.get(10).unwrap();
+    assert_eq!(nyse.mic.as_deref(), Some("XNYS"));
+    assert_eq!(nyse.type_, Type::Exchange);
   }
 }
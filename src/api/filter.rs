@@ -0,0 +1,96 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fmt::Display;
+
+use url::form_urlencoded::Serializer;
+
+
+/// A filter comparing a field against a value.
+///
+/// Multiple filters on the same field are combined, e.g., to request a
+/// range of values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter<T> {
+  /// The field has to equal the value.
+  Equal(T),
+  /// The field has to be greater than the value.
+  GreaterThan(T),
+  /// The field has to be greater than or equal to the value.
+  GreaterThanOrEqual(T),
+  /// The field has to be less than the value.
+  LessThan(T),
+  /// The field has to be less than or equal to the value.
+  LessThanOrEqual(T),
+}
+
+impl<T> Filter<T> {
+  /// Retrieve the suffix to append to the field name as well as the
+  /// value to compare against.
+  fn split(&self) -> (&'static str, &T) {
+    match self {
+      Self::Equal(value) => ("", value),
+      Self::GreaterThan(value) => (".gt", value),
+      Self::GreaterThanOrEqual(value) => (".gte", value),
+      Self::LessThan(value) => (".lt", value),
+      Self::LessThanOrEqual(value) => (".lte", value),
+    }
+  }
+
+  /// Convert the value compared against, retaining the comparison.
+  pub(crate) fn map<U, F>(&self, f: F) -> Filter<U>
+  where
+    F: FnOnce(&T) -> U,
+  {
+    match self {
+      Self::Equal(value) => Filter::Equal(f(value)),
+      Self::GreaterThan(value) => Filter::GreaterThan(f(value)),
+      Self::GreaterThanOrEqual(value) => Filter::GreaterThanOrEqual(f(value)),
+      Self::LessThan(value) => Filter::LessThan(f(value)),
+      Self::LessThanOrEqual(value) => Filter::LessThanOrEqual(f(value)),
+    }
+  }
+}
+
+
+/// Append the given filters on a field to a query.
+pub(crate) fn append_filters<T>(
+  query: &mut Serializer<'_, String>,
+  field: &str,
+  filters: &[Filter<T>],
+) where
+  T: Display,
+{
+  for filter in filters {
+    let (suffix, value) = filter.split();
+    query.append_pair(&format!("{}{}", field, suffix), &value.to_string());
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use chrono::NaiveDate;
+
+
+  /// Check that filters are encoded as expected.
+  #[test]
+  fn encode_filters() {
+    let start = NaiveDate::from_str("2021-01-01").unwrap();
+    let end = NaiveDate::from_str("2022-01-01").unwrap();
+    let mut query = Serializer::new(String::new());
+    let filters = [Filter::GreaterThanOrEqual(start), Filter::LessThan(end)];
+    append_filters(&mut query, "date", &filters);
+    append_filters(&mut query, "amount", &[Filter::Equal(1)]);
+    append_filters::<u8>(&mut query, "empty", &[]);
+
+    assert_eq!(
+      query.finish(),
+      "date.gte=2021-01-01&date.lt=2022-01-01&amount=1"
+    );
+  }
+}
@@ -0,0 +1,491 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::filter::append_filters;
+use crate::api::filter::Filter;
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// The time frame covered by financials.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Timeframe {
+  /// The financials cover a fiscal year.
+  #[serde(rename = "annual")]
+  Annual,
+  /// The financials cover a fiscal quarter.
+  #[serde(rename = "quarterly")]
+  Quarterly,
+  /// The financials cover the trailing twelve months.
+  #[serde(rename = "ttm")]
+  TrailingTwelveMonths,
+}
+
+impl AsRef<str> for Timeframe {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Timeframe::Annual => "annual",
+      Timeframe::Quarterly => "quarterly",
+      Timeframe::TrailingTwelveMonths => "ttm",
+    }
+  }
+}
+
+
+/// The field to sort financials by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the filing date.
+  FilingDate,
+  /// Sort by the period of report date.
+  PeriodOfReportDate,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::FilingDate => "filing_date",
+      Sort::PeriodOfReportDate => "period_of_report_date",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/vX/reference/financials`
+/// endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FinancialsReq {
+  /// Only report financials of this ticker.
+  pub ticker: Option<String>,
+  /// Only report financials of the company with this CIK.
+  pub cik: Option<String>,
+  /// Filters on the date of the period the financials are reported
+  /// for.
+  pub period_of_report_date: Vec<Filter<NaiveDate>>,
+  /// Only report financials covering this time frame.
+  pub timeframe: Option<Timeframe>,
+  /// Filters on the filing date.
+  pub filing_date: Vec<Filter<NaiveDate>>,
+  /// The order in which to report financials.
+  pub order: Option<Order>,
+  /// The field to sort financials by.
+  pub sort: Option<Sort>,
+  /// The maximum number of financials to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// A single line item of a financial statement.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct DataPoint {
+  /// A human readable label of the line item.
+  #[serde(rename = "label")]
+  pub label: String,
+  /// The order of the line item within its statement.
+  #[serde(rename = "order")]
+  pub order: Option<i64>,
+  /// The unit of the value, e.g., `USD`.
+  #[serde(rename = "unit")]
+  pub unit: String,
+  /// The value.
+  #[serde(rename = "value")]
+  pub value: Num,
+}
+
+
+/// An income statement.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct IncomeStatement {
+  /// The revenues.
+  #[serde(rename = "revenues")]
+  pub revenues: Option<DataPoint>,
+  /// The cost of revenue.
+  #[serde(rename = "cost_of_revenue")]
+  pub cost_of_revenue: Option<DataPoint>,
+  /// The gross profit.
+  #[serde(rename = "gross_profit")]
+  pub gross_profit: Option<DataPoint>,
+  /// The operating expenses.
+  #[serde(rename = "operating_expenses")]
+  pub operating_expenses: Option<DataPoint>,
+  /// The operating income or loss.
+  #[serde(rename = "operating_income_loss")]
+  pub operating_income_loss: Option<DataPoint>,
+  /// The income tax expense or benefit.
+  #[serde(rename = "income_tax_expense_benefit")]
+  pub income_tax_expense_benefit: Option<DataPoint>,
+  /// The net income or loss.
+  #[serde(rename = "net_income_loss")]
+  pub net_income_loss: Option<DataPoint>,
+  /// The basic earnings per share.
+  #[serde(rename = "basic_earnings_per_share")]
+  pub basic_earnings_per_share: Option<DataPoint>,
+  /// The diluted earnings per share.
+  #[serde(rename = "diluted_earnings_per_share")]
+  pub diluted_earnings_per_share: Option<DataPoint>,
+  /// All other line items, keyed by their name.
+  #[serde(flatten)]
+  pub other: BTreeMap<String, DataPoint>,
+}
+
+
+/// A balance sheet.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct BalanceSheet {
+  /// The total assets.
+  #[serde(rename = "assets")]
+  pub assets: Option<DataPoint>,
+  /// The current assets.
+  #[serde(rename = "current_assets")]
+  pub current_assets: Option<DataPoint>,
+  /// The noncurrent assets.
+  #[serde(rename = "noncurrent_assets")]
+  pub noncurrent_assets: Option<DataPoint>,
+  /// The total liabilities.
+  #[serde(rename = "liabilities")]
+  pub liabilities: Option<DataPoint>,
+  /// The current liabilities.
+  #[serde(rename = "current_liabilities")]
+  pub current_liabilities: Option<DataPoint>,
+  /// The noncurrent liabilities.
+  #[serde(rename = "noncurrent_liabilities")]
+  pub noncurrent_liabilities: Option<DataPoint>,
+  /// The total equity.
+  #[serde(rename = "equity")]
+  pub equity: Option<DataPoint>,
+  /// The total liabilities and equity.
+  #[serde(rename = "liabilities_and_equity")]
+  pub liabilities_and_equity: Option<DataPoint>,
+  /// All other line items, keyed by their name.
+  #[serde(flatten)]
+  pub other: BTreeMap<String, DataPoint>,
+}
+
+
+/// A cash flow statement.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CashFlowStatement {
+  /// The net cash flow.
+  #[serde(rename = "net_cash_flow")]
+  pub net_cash_flow: Option<DataPoint>,
+  /// The net cash flow from operating activities.
+  #[serde(rename = "net_cash_flow_from_operating_activities")]
+  pub net_cash_flow_from_operating_activities: Option<DataPoint>,
+  /// The net cash flow from investing activities.
+  #[serde(rename = "net_cash_flow_from_investing_activities")]
+  pub net_cash_flow_from_investing_activities: Option<DataPoint>,
+  /// The net cash flow from financing activities.
+  #[serde(rename = "net_cash_flow_from_financing_activities")]
+  pub net_cash_flow_from_financing_activities: Option<DataPoint>,
+  /// All other line items, keyed by their name.
+  #[serde(flatten)]
+  pub other: BTreeMap<String, DataPoint>,
+}
+
+
+/// A statement of comprehensive income.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ComprehensiveIncome {
+  /// The comprehensive income or loss.
+  #[serde(rename = "comprehensive_income_loss")]
+  pub comprehensive_income_loss: Option<DataPoint>,
+  /// The other comprehensive income or loss.
+  #[serde(rename = "other_comprehensive_income_loss")]
+  pub other_comprehensive_income_loss: Option<DataPoint>,
+  /// All other line items, keyed by their name.
+  #[serde(flatten)]
+  pub other: BTreeMap<String, DataPoint>,
+}
+
+
+/// The financial statements of a filing.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Statements {
+  /// The income statement.
+  #[serde(rename = "income_statement")]
+  pub income_statement: Option<IncomeStatement>,
+  /// The balance sheet.
+  #[serde(rename = "balance_sheet")]
+  pub balance_sheet: Option<BalanceSheet>,
+  /// The cash flow statement.
+  #[serde(rename = "cash_flow_statement")]
+  pub cash_flow_statement: Option<CashFlowStatement>,
+  /// The statement of comprehensive income.
+  #[serde(rename = "comprehensive_income")]
+  pub comprehensive_income: Option<ComprehensiveIncome>,
+}
+
+
+/// Financials as returned by the `/vX/reference/financials` endpoint.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Financials {
+  /// The tickers of the company.
+  #[serde(rename = "tickers", default)]
+  pub tickers: Vec<String>,
+  /// The company's name.
+  #[serde(rename = "company_name")]
+  pub company_name: Option<String>,
+  /// The company's CIK.
+  #[serde(rename = "cik")]
+  pub cik: Option<String>,
+  /// The first date of the reported period.
+  #[serde(rename = "start_date")]
+  pub start_date: Option<NaiveDate>,
+  /// The last date of the reported period.
+  #[serde(rename = "end_date")]
+  pub end_date: Option<NaiveDate>,
+  /// The date the financials were filed.
+  #[serde(rename = "filing_date")]
+  pub filing_date: Option<NaiveDate>,
+  /// The time frame the financials cover.
+  #[serde(rename = "timeframe")]
+  pub timeframe: Timeframe,
+  /// The fiscal period, e.g., `Q1` or `FY`.
+  #[serde(rename = "fiscal_period")]
+  pub fiscal_period: Option<String>,
+  /// The fiscal year.
+  #[serde(rename = "fiscal_year")]
+  pub fiscal_year: Option<String>,
+  /// The financial statements.
+  #[serde(rename = "financials")]
+  pub statements: Statements,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/vX/reference/financials` endpoint.
+  pub List(FinancialsReq),
+  Ok => Page<Financials>, [
+    /// The financials were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(_input: &Self::Input) -> Str {
+    "/vX/reference/financials".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      if let Some(ticker) = &input.ticker {
+        query.append_pair("ticker", ticker);
+      }
+      if let Some(cik) = &input.cik {
+        query.append_pair("cik", cik);
+      }
+      append_filters(&mut query, "period_of_report_date", &input.period_of_report_date);
+      if let Some(timeframe) = &input.timeframe {
+        query.append_pair("timeframe", timeframe.as_ref());
+      }
+      append_filters(&mut query, "filing_date", &input.filing_date);
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Financials;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::StreamExt as _;
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = FinancialsReq {
+      ticker: Some("AAPL".into()),
+      period_of_report_date: vec![Filter::GreaterThanOrEqual(
+        NaiveDate::from_str("2021-01-01").unwrap(),
+      )],
+      timeframe: Some(Timeframe::Quarterly),
+      filing_date: vec![Filter::LessThan(NaiveDate::from_str("2022-01-01").unwrap())],
+      order: Some(Order::Descending),
+      sort: Some(Sort::FilingDate),
+      limit: Some(5),
+      ..Default::default()
+    };
+
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "ticker=AAPL&period_of_report_date.gte=2021-01-01&timeframe=quarterly&filing_date.lt=2022-01-01&order=desc&sort=filing_date&limit=5"
+    );
+  }
+
+  /// Make sure that we can deserialize a page of financials.
+  #[test]
+  fn deserialize_financials() {
+    let response = r#"{
+  "count": 1,
+  "next_url": "https://api.polygon.io/vX/reference/financials?cursor=YXA9MjAyMjA0MjkmYXM9JmxpbWl0PTE",
+  "request_id": "55eb92ed43b25568ab0cce159830ea34",
+  "results": [
+    {
+      "cik": "0000320193",
+      "company_name": "Apple Inc.",
+      "end_date": "2022-03-26",
+      "filing_date": "2022-04-29",
+      "financials": {
+        "balance_sheet": {
+          "assets": {"label": "Assets", "order": 100, "unit": "USD", "value": 350662000000},
+          "equity": {"label": "Equity", "order": 1400, "unit": "USD", "value": 67399000000},
+          "fixed_assets": {"label": "Fixed Assets", "order": 320, "unit": "USD", "value": 39304000000}
+        },
+        "cash_flow_statement": {
+          "net_cash_flow": {"label": "Net Cash Flow", "order": 1100, "unit": "USD", "value": -3900000000}
+        },
+        "comprehensive_income": {
+          "comprehensive_income_loss": {"label": "Comprehensive Income/Loss", "order": 100, "unit": "USD", "value": 21983000000}
+        },
+        "income_statement": {
+          "basic_earnings_per_share": {"label": "Basic Earnings Per Share", "order": 4200, "unit": "USD / shares", "value": 1.54},
+          "revenues": {"label": "Revenues", "order": 100, "unit": "USD", "value": 97278000000},
+          "research_and_development": {"label": "Research and Development", "order": 1030, "unit": "USD", "value": 6387000000}
+        }
+      },
+      "fiscal_period": "Q2",
+      "fiscal_year": "2022",
+      "source_filing_file_url": "https://api.polygon.io/v1/reference/sec/filings/0000320193-22-000059/files/aapl-20220326_htm.xml",
+      "source_filing_url": "https://api.polygon.io/v1/reference/sec/filings/0000320193-22-000059",
+      "start_date": "2021-12-26",
+      "tickers": ["AAPL"],
+      "timeframe": "quarterly"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Financials>>(response).unwrap();
+    assert!(page.cursor.is_some());
+    assert_eq!(page.results.len(), 1);
+
+    let financials = &page.results[0];
+    assert_eq!(financials.tickers, vec!["AAPL".to_string()]);
+    assert_eq!(financials.cik.as_deref(), Some("0000320193"));
+    assert_eq!(financials.timeframe, Timeframe::Quarterly);
+    assert_eq!(financials.fiscal_period.as_deref(), Some("Q2"));
+    assert_eq!(
+      financials.filing_date,
+      Some(NaiveDate::from_str("2022-04-29").unwrap())
+    );
+
+    let statements = &financials.statements;
+    let income = statements.income_statement.as_ref().unwrap();
+    assert_eq!(
+      income.revenues.as_ref().unwrap().value,
+      Num::from(97_278_000_000i64)
+    );
+    assert_eq!(
+      income.basic_earnings_per_share.as_ref().unwrap().value,
+      Num::new(154, 100)
+    );
+    assert_eq!(income.net_income_loss, None);
+    assert_eq!(
+      income.other["research_and_development"].label,
+      "Research and Development"
+    );
+
+    let balance = statements.balance_sheet.as_ref().unwrap();
+    assert_eq!(balance.assets.as_ref().unwrap().unit, "USD");
+    assert_eq!(balance.assets.as_ref().unwrap().order, Some(100));
+    assert_eq!(balance.other.len(), 1);
+    assert!(balance.other.contains_key("fixed_assets"));
+
+    let cash_flow = statements.cash_flow_statement.as_ref().unwrap();
+    assert_eq!(
+      cash_flow.net_cash_flow.as_ref().unwrap().value,
+      Num::from(-3_900_000_000i64)
+    );
+    assert!(cash_flow.other.is_empty());
+
+    let comprehensive = statements.comprehensive_income.as_ref().unwrap();
+    assert!(comprehensive.comprehensive_income_loss.is_some());
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_financials() {
+    let client = Client::from_env().unwrap();
+    let request = FinancialsReq {
+      ticker: Some("AAPL".into()),
+      timeframe: Some(Timeframe::Annual),
+      limit: Some(2),
+      ..Default::default()
+    };
+
+    let financials = client
+      .pages::<List>(request)
+      .take(1)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert_eq!(financials.len(), 2);
+    assert!(financials
+      .iter()
+      .all(|financials| financials.timeframe == Timeframe::Annual));
+  }
+}
@@ -0,0 +1,171 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::serde::ts_nanoseconds::deserialize as datetime_from_nanos;
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use crate::api::response::Response;
+use crate::events;
+use crate::Str;
+
+
+/// The most recent national best bid and offer (NBBO) quote for a
+/// ticker as returned by the `/v2/last/nbbo/<symbol>` endpoint.
+///
+/// The type mirrors `events::Quote` and can be converted into one.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Quote {
+  /// The stock's symbol.
+  #[serde(rename = "T")]
+  pub symbol: String,
+  /// The exchange providing the bid.
+  #[serde(rename = "x")]
+  pub bid_exchange: u64,
+  /// The bid price.
+  #[serde(rename = "p")]
+  pub bid_price: Num,
+  /// The bid quantity, in round lots.
+  #[serde(rename = "s")]
+  pub bid_quantity: u64,
+  /// The exchange providing the ask.
+  #[serde(rename = "X")]
+  pub ask_exchange: u64,
+  /// The ask price.
+  #[serde(rename = "P")]
+  pub ask_price: Num,
+  /// The ask quantity, in round lots.
+  #[serde(rename = "S")]
+  pub ask_quantity: u64,
+  /// The quote's timestamp, i.e., the time at which the SIP received
+  /// it.
+  #[serde(rename = "t", deserialize_with = "datetime_from_nanos")]
+  pub timestamp: DateTime<Utc>,
+  /// The conditions of the quote.
+  #[serde(rename = "c", default)]
+  pub conditions: Vec<u64>,
+  /// The indicators of the quote.
+  #[serde(rename = "i", default)]
+  pub indicators: Vec<u64>,
+  /// The sequence number, unique per ticker and day.
+  #[serde(rename = "q")]
+  pub sequence_number: u64,
+  /// The tape the quote was reported to.
+  #[serde(rename = "z")]
+  pub tape: Option<u8>,
+}
+
+impl From<Quote> for events::Quote {
+  fn from(quote: Quote) -> Self {
+    Self {
+      symbol: quote.symbol,
+      bid_exchange: quote.bid_exchange,
+      bid_price: quote.bid_price,
+      bid_quantity: quote.bid_quantity,
+      ask_exchange: quote.ask_exchange,
+      ask_price: quote.ask_price,
+      ask_quantity: quote.ask_quantity,
+      timestamp: quote.timestamp,
+      condition: quote.conditions.first().copied(),
+    }
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v2/last/nbbo/<symbol>` endpoint.
+  pub Get(String),
+  Ok => Response<Quote>, [
+    /// The last quote was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// No quote was found for the given symbol.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v2/last/nbbo/{}", input).into()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Make sure that we can deserialize the last quote.
+  #[test]
+  fn deserialize_last_quote() {
+    let response = r#"{
+  "request_id": "b84e24636301f19f88e0dfbf9a45ed5c",
+  "results": {
+    "P": 127.98,
+    "S": 7,
+    "T": "AAPL",
+    "X": 19,
+    "p": 127.96,
+    "q": 83480742,
+    "s": 1,
+    "t": 1617827221349730300,
+    "x": 11,
+    "y": 1617827221349366000,
+    "z": 3
+  },
+  "status": "OK"
+}"#;
+
+    let quote = from_json::<Response<Quote>>(response)
+      .unwrap()
+      .into_result()
+      .unwrap();
+    assert_eq!(quote.symbol, "AAPL");
+    assert_eq!(quote.conditions, Vec::<u64>::new());
+    assert_eq!(quote.sequence_number, 83480742);
+    assert_eq!(quote.tape, Some(3));
+
+    let quote = events::Quote::from(quote);
+    assert_eq!(quote.bid_exchange, 11);
+    assert_eq!(quote.bid_price, Num::new(12796, 100));
+    assert_eq!(quote.bid_quantity, 1);
+    assert_eq!(quote.ask_exchange, 19);
+    assert_eq!(quote.ask_price, Num::new(12798, 100));
+    assert_eq!(quote.ask_quantity, 7);
+    assert_eq!(
+      quote.timestamp,
+      DateTime::parse_from_rfc3339("2021-04-07T20:27:01.3497303Z").unwrap()
+    );
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_last_spy_quote() {
+    let client = Client::from_env().unwrap();
+    let quote = client
+      .issue::<Get>("SPY".into())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    assert_eq!(quote.symbol, "SPY");
+    assert!(quote.bid_price <= quote.ask_price);
+  }
+}
@@ -0,0 +1,158 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::serde::ts_nanoseconds::deserialize as datetime_from_nanos;
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use crate::api::response::Response;
+use crate::events;
+use crate::Str;
+
+
+/// The most recent trade for a ticker as returned by the
+/// `/v2/last/trade/<symbol>` endpoint.
+///
+/// The type mirrors `events::Trade` and can be converted into one.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Trade {
+  /// The stock's symbol.
+  #[serde(rename = "T")]
+  pub symbol: String,
+  /// The exchange the trade occurred on.
+  #[serde(rename = "x")]
+  pub exchange: u64,
+  /// The price.
+  #[serde(rename = "p")]
+  pub price: Num,
+  /// The number of shares traded.
+  #[serde(rename = "s")]
+  pub quantity: u64,
+  /// The trade's timestamp, i.e., the time at which the SIP received
+  /// it.
+  #[serde(rename = "t", deserialize_with = "datetime_from_nanos")]
+  pub timestamp: DateTime<Utc>,
+  /// The conditions of the trade.
+  #[serde(rename = "c", default)]
+  pub conditions: Vec<u64>,
+  /// The trade ID, unique per ticker, exchange, and day.
+  #[serde(rename = "i")]
+  pub id: String,
+  /// The sequence number, unique per ticker and day.
+  #[serde(rename = "q")]
+  pub sequence_number: u64,
+  /// The tape the trade was reported to.
+  #[serde(rename = "z")]
+  pub tape: Option<u8>,
+}
+
+impl From<Trade> for events::Trade {
+  fn from(trade: Trade) -> Self {
+    Self {
+      symbol: trade.symbol,
+      exchange: trade.exchange,
+      price: trade.price,
+      quantity: trade.quantity,
+      timestamp: trade.timestamp,
+      conditions: trade.conditions,
+    }
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v2/last/trade/<symbol>` endpoint.
+  pub Get(String),
+  Ok => Response<Trade>, [
+    /// The last trade was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// No trade was found for the given symbol.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v2/last/trade/{}", input).into()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Make sure that we can deserialize the last trade.
+  #[test]
+  fn deserialize_last_trade() {
+    let response = r#"{
+  "request_id": "f05562305bd26ced64b98ed68b3c5d96",
+  "results": {
+    "T": "AAPL",
+    "c": [37],
+    "f": 1617901342969796400,
+    "i": "118749",
+    "p": 129.8473,
+    "q": 3135876,
+    "r": 202,
+    "s": 25,
+    "t": 1617901342969834000,
+    "x": 4,
+    "y": 1617901342968000000,
+    "z": 3
+  },
+  "status": "OK"
+}"#;
+
+    let trade = from_json::<Response<Trade>>(response)
+      .unwrap()
+      .into_result()
+      .unwrap();
+    assert_eq!(trade.symbol, "AAPL");
+    assert_eq!(trade.conditions, vec![37]);
+    assert_eq!(trade.id, "118749");
+    assert_eq!(trade.sequence_number, 3135876);
+    assert_eq!(trade.tape, Some(3));
+
+    let trade = events::Trade::from(trade);
+    assert_eq!(trade.exchange, 4);
+    assert_eq!(trade.price, Num::new(1_298_473, 10000));
+    assert_eq!(trade.quantity, 25);
+    assert_eq!(
+      trade.timestamp,
+      DateTime::parse_from_rfc3339("2021-04-08T17:02:22.969834Z").unwrap()
+    );
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_last_aapl_trade() {
+    let client = Client::from_env().unwrap();
+    let trade = client
+      .issue::<Get>("AAPL".into())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    assert_eq!(trade.symbol, "AAPL");
+    assert!(trade.price > Num::from(0));
+  }
+}
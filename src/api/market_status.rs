@@ -1,7 +1,10 @@
 // Copyright (C) 2020-2022 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use std::collections::BTreeMap;
+
 use chrono::DateTime;
+use chrono::NaiveDate;
 use chrono::Utc;
 
 use serde::de::Deserializer;
@@ -24,6 +27,19 @@ where
 }
 
 
+/// Deserialize an optional date time from a string.
+fn optional_datetime_from_str<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  struct Wrapper(#[serde(deserialize_with = "datetime_from_str")] DateTime<Utc>);
+
+  let wrapper = Option::<Wrapper>::deserialize(deserializer)?;
+  Ok(wrapper.map(|Wrapper(time)| time))
+}
+
+
 /// The market status.
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
 pub enum Status {
@@ -33,6 +49,10 @@ pub enum Status {
   /// The market is currently closed.
   #[serde(rename = "closed")]
   Closed,
+  /// The market is currently in extended hours, i.e., in the pre-market
+  /// or after-hours session.
+  #[serde(rename = "extended-hours")]
+  ExtendedHours,
   /// Any other status that we have not accounted for.
   ///
   /// Note that having any such status should be considered a bug.
@@ -41,22 +61,99 @@ pub enum Status {
 }
 
 
+/// The status of individual stock exchanges.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+pub struct Exchanges {
+  /// The status of the New York Stock Exchange.
+  #[serde(rename = "nyse")]
+  pub nyse: Status,
+  /// The status of the Nasdaq.
+  #[serde(rename = "nasdaq")]
+  pub nasdaq: Status,
+  /// The status of the over-the-counter market.
+  #[serde(rename = "otc")]
+  pub otc: Status,
+}
+
+
+/// The status of currency markets.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+pub struct Currencies {
+  /// The status of the foreign exchange market.
+  #[serde(rename = "fx")]
+  pub fx: Status,
+  /// The status of the crypto currency market.
+  #[serde(rename = "crypto")]
+  pub crypto: Status,
+}
+
+
 /// The market status as returned by the `/v1/marketstatus/now`
 /// endpoint.
-///
-/// Please note that not all fields available in a response are
-/// represented here.
-#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct Market {
   /// The status of the market as a whole.
   #[serde(rename = "market")]
   pub status: Status,
+  /// Whether the market is in the pre-market session.
+  #[serde(rename = "earlyHours")]
+  pub early_hours: bool,
+  /// Whether the market is in the after-hours session.
+  #[serde(rename = "afterHours")]
+  pub after_hours: bool,
+  /// The status of individual stock exchanges.
+  #[serde(rename = "exchanges")]
+  pub exchanges: Exchanges,
+  /// The status of currency markets.
+  #[serde(rename = "currencies")]
+  pub currencies: Currencies,
+  /// The status of index groups, keyed by the group's name, e.g.,
+  /// `s_and_p` or `dow_jones`.
+  #[serde(rename = "indicesGroups", default)]
+  pub indices: BTreeMap<String, Status>,
   /// The current server time.
   #[serde(rename = "serverTime", deserialize_with = "datetime_from_str")]
   pub server_time: DateTime<Utc>,
 }
 
 
+/// The status of the market on a holiday.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+pub enum HolidayStatus {
+  /// The market is closed for the entire day.
+  #[serde(rename = "closed")]
+  Closed,
+  /// The market closes early.
+  #[serde(rename = "early-close")]
+  EarlyClose,
+}
+
+
+/// A market holiday as returned by the `/v1/marketstatus/upcoming`
+/// endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Holiday {
+  /// The exchange the holiday applies to, e.g., `NYSE`.
+  #[serde(rename = "exchange")]
+  pub exchange: String,
+  /// The holiday's name.
+  #[serde(rename = "name")]
+  pub name: String,
+  /// The holiday's date.
+  #[serde(rename = "date")]
+  pub date: NaiveDate,
+  /// The status of the market on the holiday.
+  #[serde(rename = "status")]
+  pub status: HolidayStatus,
+  /// The time at which the market opens, if it opens at all.
+  #[serde(rename = "open", default, deserialize_with = "optional_datetime_from_str")]
+  pub open: Option<DateTime<Utc>>,
+  /// The time at which the market closes early, if it opens at all.
+  #[serde(rename = "close", default, deserialize_with = "optional_datetime_from_str")]
+  pub close: Option<DateTime<Utc>>,
+}
+
+
 Endpoint! {
   /// The representation of a GET request to the `/v1/marketstatus/now`
   /// endpoint.
@@ -73,18 +170,129 @@ Endpoint! {
 }
 
 
-#[cfg(not(target_arch = "wasm32"))]
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v1/marketstatus/upcoming` endpoint.
+  pub GetUpcoming(()),
+  Ok => Vec<Holiday>, [
+    /// The upcoming holidays were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetUpcomingError, []
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v1/marketstatus/upcoming".into()
+  }
+}
+
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  use std::str::FromStr as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
   use chrono::naive::NaiveTime;
 
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
   use test_log::test;
 
+  #[cfg(not(target_arch = "wasm32"))]
   use crate::Client;
 
 
+  /// Make sure that we can deserialize the market status.
+  #[test]
+  fn deserialize_market_status() {
+    let response = r#"{
+  "afterHours": true,
+  "currencies": {
+    "crypto": "open",
+    "fx": "open"
+  },
+  "earlyHours": false,
+  "exchanges": {
+    "nasdaq": "extended-hours",
+    "nyse": "extended-hours",
+    "otc": "closed"
+  },
+  "indicesGroups": {
+    "s_and_p": "open",
+    "societe_generale": "open",
+    "msci": "open",
+    "ftse_russell": "open",
+    "mstar": "open",
+    "mstarc": "open",
+    "cccy": "open",
+    "cgi": "open",
+    "nasdaq": "open",
+    "dow_jones": "open"
+  },
+  "market": "extended-hours",
+  "serverTime": "2020-11-10T17:37:37-05:00"
+}"#;
+
+    let market = from_json::<Market>(response).unwrap();
+    assert_eq!(market.status, Status::ExtendedHours);
+    assert!(!market.early_hours);
+    assert!(market.after_hours);
+    assert_eq!(market.exchanges.nyse, Status::ExtendedHours);
+    assert_eq!(market.exchanges.nasdaq, Status::ExtendedHours);
+    assert_eq!(market.exchanges.otc, Status::Closed);
+    assert_eq!(market.currencies.fx, Status::Open);
+    assert_eq!(market.currencies.crypto, Status::Open);
+    assert_eq!(market.indices.len(), 10);
+    assert_eq!(market.indices["dow_jones"], Status::Open);
+    assert_eq!(
+      market.server_time,
+      DateTime::parse_from_rfc3339("2020-11-10T17:37:37-05:00").unwrap()
+    );
+  }
+
+  /// Make sure that we can deserialize upcoming holidays.
+  #[test]
+  fn deserialize_holidays() {
+    let response = r#"[
+  {
+    "date": "2020-11-26",
+    "exchange": "NYSE",
+    "name": "Thanksgiving",
+    "status": "closed"
+  },
+  {
+    "close": "2020-11-27T18:00:00.000Z",
+    "date": "2020-11-27",
+    "exchange": "NASDAQ",
+    "name": "Thanksgiving",
+    "open": "2020-11-27T14:30:00.000Z",
+    "status": "early-close"
+  }
+]"#;
+
+    let holidays = from_json::<Vec<Holiday>>(response).unwrap();
+    assert_eq!(holidays.len(), 2);
+    assert_eq!(holidays[0].exchange, "NYSE");
+    assert_eq!(holidays[0].name, "Thanksgiving");
+    assert_eq!(holidays[0].date, NaiveDate::from_str("2020-11-26").unwrap());
+    assert_eq!(holidays[0].status, HolidayStatus::Closed);
+    assert_eq!(holidays[0].open, None);
+    assert_eq!(holidays[0].close, None);
+
+    assert_eq!(holidays[1].status, HolidayStatus::EarlyClose);
+    assert_eq!(
+      holidays[1].open,
+      Some(DateTime::parse_from_rfc3339("2020-11-27T09:30:00-05:00").unwrap().into())
+    );
+    assert_eq!(
+      holidays[1].close,
+      Some(DateTime::parse_from_rfc3339("2020-11-27T13:00:00-05:00").unwrap().into())
+    );
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
   #[test(tokio::test)]
   async fn request_market_status() {
     let client = Client::from_env().unwrap();
@@ -102,4 +310,19 @@ mod tests {
       assert!(market_time < close);
     }
   }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_upcoming_holidays() {
+    let client = Client::from_env().unwrap();
+    let holidays = client.issue::<GetUpcoming>(()).await.unwrap();
+    let today = Utc::now().naive_utc().date();
+
+    assert!(!holidays.is_empty());
+    assert!(holidays.iter().all(|holiday| holiday.date >= today));
+    assert!(holidays
+      .iter()
+      .filter(|holiday| holiday.status == HolidayStatus::Closed)
+      .all(|holiday| holiday.open.is_none() && holiday.close.is_none()));
+  }
 }
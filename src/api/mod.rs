@@ -1,22 +1,64 @@
-// Copyright (C) 2020-2021 Daniel Mueller <deso@posteo.net>
+// Copyright (C) 2020-2022 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod filter;
+mod page;
 mod response;
 
 /// Definitions surrounding aggregate prices of stocks.
 pub mod aggregates;
+/// Definitions for retrieving trade and quote conditions.
+pub mod conditions;
+/// Definitions for retrieving dividends.
+pub mod dividends;
 /// Definitions pertaining the available exchanges.
 pub mod exchanges;
+/// Definitions for retrieving company financials.
+pub mod financials;
+/// Definitions for retrieving the most recent quote for a ticker.
+pub mod last_quote;
+/// Definitions for retrieving the most recent trade for a ticker.
+pub mod last_trade;
 /// Definitions pertaining the available locales.
 pub mod locales;
 /// Definitions for retrieving the current market status.
 pub mod market_status;
 /// Definitions pertaining the available markets.
 pub mod markets;
+/// Definitions for retrieving news articles.
+pub mod news;
+/// Definitions for retrieving the daily open and close of a ticker.
+pub mod open_close;
+/// Definitions for retrieving option contracts.
+pub mod options;
+/// Definitions for retrieving snapshots of option contracts.
+pub mod options_snapshot;
+/// Definitions for retrieving historic quotes.
+pub mod quotes;
+/// Definitions for retrieving snapshots of tickers' market data.
+pub mod snapshot;
+/// Definitions for retrieving stock splits and adjusting aggregates
+/// for them.
+pub mod splits;
 /// Definitions pertaining a ticker.
 pub mod ticker;
+/// Definitions for retrieving the history of a ticker.
+pub mod ticker_events;
 /// Definitions for retrieving the available ticker types.
 pub mod ticker_types;
+/// Definitions for listing and searching tickers.
+pub mod tickers;
+/// Definitions for retrieving historic trades.
+pub mod trades;
+
+/// A filter comparing a field against a value.
+pub use filter::Filter;
+/// The order in which paginated results are reported.
+pub use page::Order;
+/// A single page of results as reported by paginated endpoints.
+pub use page::Page;
+/// A trait for endpoints reporting results in pages.
+pub use page::Paginated;
 
 /// A response type used in certain API calls.
 pub use response::Response;
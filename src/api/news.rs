@@ -0,0 +1,345 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// The field to sort news articles by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the article's publication time.
+  Published,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Published => "published_utc",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v2/reference/news` endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NewsReq {
+  /// Only report articles about this ticker.
+  pub ticker: Option<String>,
+  /// Only report articles published at or after this time.
+  pub start: Option<DateTime<Utc>>,
+  /// Only report articles published before this time.
+  pub end: Option<DateTime<Utc>>,
+  /// The order in which to report articles.
+  pub order: Option<Order>,
+  /// The field to sort articles by.
+  pub sort: Option<Sort>,
+  /// The maximum number of articles to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// The publisher of a news article.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Publisher {
+  /// The publisher's name.
+  #[serde(rename = "name")]
+  pub name: String,
+  /// The URL of the publisher's homepage.
+  #[serde(rename = "homepage_url")]
+  pub homepage_url: Option<String>,
+  /// The URL of the publisher's logo.
+  #[serde(rename = "logo_url")]
+  pub logo_url: Option<String>,
+  /// The URL of the publisher's favicon.
+  #[serde(rename = "favicon_url")]
+  pub favicon_url: Option<String>,
+}
+
+
+/// The sentiment of a news article towards a ticker.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Sentiment {
+  /// The article is positive about the ticker.
+  #[serde(rename = "positive")]
+  Positive,
+  /// The article is neutral about the ticker.
+  #[serde(rename = "neutral")]
+  Neutral,
+  /// The article is negative about the ticker.
+  #[serde(rename = "negative")]
+  Negative,
+  /// Any other sentiment.
+  #[serde(other)]
+  Other,
+}
+
+
+/// An insight into how a news article relates to a ticker.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Insight {
+  /// The ticker the insight is about.
+  #[serde(rename = "ticker")]
+  pub ticker: String,
+  /// The article's sentiment towards the ticker.
+  #[serde(rename = "sentiment")]
+  pub sentiment: Sentiment,
+  /// The reasoning behind the sentiment.
+  #[serde(rename = "sentiment_reasoning")]
+  pub sentiment_reasoning: Option<String>,
+}
+
+
+/// A news article as returned by the `/v2/reference/news` endpoint.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Article {
+  /// The article's ID.
+  #[serde(rename = "id")]
+  pub id: String,
+  /// The article's publisher.
+  #[serde(rename = "publisher")]
+  pub publisher: Publisher,
+  /// The article's title.
+  #[serde(rename = "title")]
+  pub title: String,
+  /// The article's author.
+  #[serde(rename = "author")]
+  pub author: Option<String>,
+  /// The time at which the article was published.
+  #[serde(rename = "published_utc")]
+  pub published: DateTime<Utc>,
+  /// The URL of the article.
+  #[serde(rename = "article_url")]
+  pub article_url: String,
+  /// The URL of the article's image, if any.
+  #[serde(rename = "image_url")]
+  pub image_url: Option<String>,
+  /// A description of the article.
+  #[serde(rename = "description")]
+  pub description: Option<String>,
+  /// The tickers mentioned in the article.
+  #[serde(rename = "tickers", default)]
+  pub tickers: Vec<String>,
+  /// The keywords associated with the article.
+  #[serde(rename = "keywords", default)]
+  pub keywords: Vec<String>,
+  /// Per-ticker insights, including the sentiment.
+  #[serde(rename = "insights", default)]
+  pub insights: Vec<Insight>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the `/v2/reference/news`
+  /// endpoint.
+  pub List(NewsReq),
+  Ok => Page<Article>, [
+    /// The news articles were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/reference/news".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      if let Some(ticker) = &input.ticker {
+        query.append_pair("ticker", ticker);
+      }
+      if let Some(start) = &input.start {
+        let start = start.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        query.append_pair("published_utc.gte", &start);
+      }
+      if let Some(end) = &input.end {
+        let end = end.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+        query.append_pair("published_utc.lt", &end);
+      }
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Article;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::StreamExt as _;
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = NewsReq {
+      ticker: Some("AAPL".into()),
+      start: Some(DateTime::parse_from_rfc3339("2021-11-01T00:00:00Z").unwrap().into()),
+      end: Some(DateTime::parse_from_rfc3339("2021-11-02T00:00:00Z").unwrap().into()),
+      order: Some(Order::Descending),
+      sort: Some(Sort::Published),
+      limit: Some(10),
+      cursor: None,
+    };
+
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "ticker=AAPL&published_utc.gte=2021-11-01T00%3A00%3A00Z&published_utc.lt=2021-11-02T00%3A00%3A00Z&order=desc&sort=published_utc&limit=10"
+    );
+  }
+
+  /// Make sure that we can deserialize a page of news articles.
+  #[test]
+  fn deserialize_articles() {
+    let response = r#"{
+  "count": 1,
+  "next_url": "https://api.polygon.io:443/v2/reference/news?cursor=eyJsaW1pdCI6MSwic29ydCI6InB1Ymxpc2hlZF91dGMifQ",
+  "request_id": "831afdb0b8078549fed053476984947a",
+  "results": [
+    {
+      "amp_url": "https://m.uk.investing.com/news/stock-market-news/markets-are-underestimating-fed-cuts-ubs-3559968?ampMode=1",
+      "article_url": "https://uk.investing.com/news/stock-market-news/markets-are-underestimating-fed-cuts-ubs-3559968",
+      "author": "Sam Boughedda",
+      "description": "UBS analysts warn that markets are underestimating the extent of future interest rate cuts.",
+      "id": "8ec638777ca03b553ae516761c2a22ba2fdd2f37befae3ab6fdab74e9e5193eb",
+      "image_url": "https://i-invdn-com.investing.com/news/LYNXNPEC4I0AL_L.jpg",
+      "insights": [
+        {
+          "sentiment": "positive",
+          "sentiment_reasoning": "UBS analysts are providing a bullish outlook on the extent of future Federal Reserve rate cuts.",
+          "ticker": "UBS"
+        }
+      ],
+      "keywords": ["Federal Reserve", "interest rates", "economic data"],
+      "published_utc": "2024-06-24T18:33:53Z",
+      "publisher": {
+        "favicon_url": "https://s3.polygon.io/public/assets/news/favicons/investing.ico",
+        "homepage_url": "https://www.investing.com/",
+        "logo_url": "https://s3.polygon.io/public/assets/news/logos/investing.png",
+        "name": "Investing.com"
+      },
+      "tickers": ["UBS"],
+      "title": "Markets are underestimating Fed cuts: UBS"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Article>>(response).unwrap();
+    assert_eq!(
+      page.cursor.as_deref(),
+      Some("eyJsaW1pdCI6MSwic29ydCI6InB1Ymxpc2hlZF91dGMifQ")
+    );
+    assert_eq!(page.results.len(), 1);
+
+    let article = &page.results[0];
+    assert_eq!(article.title, "Markets are underestimating Fed cuts: UBS");
+    assert_eq!(article.author.as_deref(), Some("Sam Boughedda"));
+    assert_eq!(
+      article.published,
+      DateTime::parse_from_rfc3339("2024-06-24T18:33:53Z").unwrap()
+    );
+    assert_eq!(article.publisher.name, "Investing.com");
+    assert_eq!(article.tickers, vec!["UBS".to_string()]);
+    assert_eq!(article.keywords.len(), 3);
+    assert_eq!(article.insights.len(), 1);
+    assert_eq!(article.insights[0].ticker, "UBS");
+    assert_eq!(article.insights[0].sentiment, Sentiment::Positive);
+  }
+
+  /// Check that an unknown sentiment does not prevent deserialization
+  /// of an article's insights.
+  #[test]
+  fn deserialize_unknown_sentiment() {
+    let response = r#"{"sentiment": "mixed", "ticker": "AAPL"}"#;
+    let insight = from_json::<Insight>(response).unwrap();
+    assert_eq!(insight.ticker, "AAPL");
+    assert_eq!(insight.sentiment, Sentiment::Other);
+    assert_eq!(insight.sentiment_reasoning, None);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_news() {
+    let client = Client::from_env().unwrap();
+    let request = NewsReq {
+      ticker: Some("AAPL".into()),
+      start: Some(DateTime::parse_from_rfc3339("2021-11-01T00:00:00Z").unwrap().into()),
+      end: Some(DateTime::parse_from_rfc3339("2021-11-08T00:00:00Z").unwrap().into()),
+      limit: Some(10),
+      ..Default::default()
+    };
+
+    let articles = client
+      .pages::<List>(request.clone())
+      .take(2)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert!(!articles.is_empty());
+    assert!(articles
+      .iter()
+      .all(|article| article.tickers.iter().any(|ticker| ticker == "AAPL")));
+    assert!(articles
+      .iter()
+      .all(|article| article.published >= request.start.unwrap()));
+  }
+}
@@ -0,0 +1,166 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::NaiveDate;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use crate::Str;
+
+
+/// A GET request to be made to the `/v1/open-close/<symbol>/<date>`
+/// endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenCloseReq {
+  /// The ticker symbol to request the open and close for.
+  pub symbol: String,
+  /// The date to request the open and close for.
+  pub date: NaiveDate,
+}
+
+
+/// The open, close, and extended hours prices of a ticker on a given
+/// day, as returned by the `/v1/open-close/<symbol>/<date>` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct OpenClose {
+  /// The ticker symbol.
+  #[serde(rename = "symbol")]
+  pub symbol: String,
+  /// The date the prices are for.
+  #[serde(rename = "from")]
+  pub date: NaiveDate,
+  /// The official open price.
+  #[serde(rename = "open")]
+  pub open_price: Num,
+  /// The high price.
+  #[serde(rename = "high")]
+  pub high_price: Num,
+  /// The low price.
+  #[serde(rename = "low")]
+  pub low_price: Num,
+  /// The official close price.
+  #[serde(rename = "close")]
+  pub close_price: Num,
+  /// The trade volume.
+  ///
+  /// This field's type is float because Polygon may use exponential
+  /// format for the number.
+  #[serde(rename = "volume")]
+  pub volume: f64,
+  /// The open price of the pre-market session.
+  #[serde(rename = "preMarket")]
+  pub pre_market: Option<Num>,
+  /// The close price of the after-hours session.
+  #[serde(rename = "afterHours")]
+  pub after_hours: Option<Num>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v1/open-close/<symbol>/<date>` endpoint.
+  pub Get(OpenCloseReq),
+  Ok => OpenClose, [
+    /// The open and close were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// No data was found for the given symbol and date.
+    ///
+    /// This error will also occur for dates that were not trading
+    /// days.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!(
+      "/v1/open-close/{}/{}",
+      input.symbol,
+      input.date.format("%Y-%m-%d"),
+    ).into()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::RequestError;
+
+
+  /// Make sure that we can deserialize a daily open and close.
+  #[test]
+  fn deserialize_open_close() {
+    let response = r#"{
+  "afterHours": 322.1,
+  "close": 325.12,
+  "from": "2020-10-14",
+  "high": 326.2,
+  "low": 322.3,
+  "open": 324.66,
+  "preMarket": 324.5,
+  "status": "OK",
+  "symbol": "AAPL",
+  "volume": 26122646
+}"#;
+
+    let open_close = from_json::<OpenClose>(response).unwrap();
+    assert_eq!(open_close.symbol, "AAPL");
+    assert_eq!(open_close.date, NaiveDate::from_str("2020-10-14").unwrap());
+    assert_eq!(open_close.open_price, Num::new(32466, 100));
+    assert_eq!(open_close.high_price, Num::new(3262, 10));
+    assert_eq!(open_close.low_price, Num::new(3223, 10));
+    assert_eq!(open_close.close_price, Num::new(32512, 100));
+    assert!(
+      (open_close.volume - 26_122_646f64).abs() <= f64::EPSILON,
+      "{}",
+      open_close.volume
+    );
+    assert_eq!(open_close.pre_market, Some(Num::new(3245, 10)));
+    assert_eq!(open_close.after_hours, Some(Num::new(3221, 10)));
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_open_close() {
+    let client = Client::from_env().unwrap();
+    let request = OpenCloseReq {
+      symbol: "AAPL".into(),
+      date: NaiveDate::from_str("2021-11-01").unwrap(),
+    };
+    let open_close = client.issue::<Get>(request).await.unwrap();
+
+    assert_eq!(open_close.symbol, "AAPL");
+    assert_eq!(open_close.date, NaiveDate::from_str("2021-11-01").unwrap());
+    assert!(open_close.low_price <= open_close.high_price);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_open_close_on_holiday() {
+    let client = Client::from_env().unwrap();
+    let request = OpenCloseReq {
+      symbol: "AAPL".into(),
+      date: NaiveDate::from_str("2021-12-25").unwrap(),
+    };
+    let result = client.issue::<Get>(request).await;
+
+    match result {
+      Err(RequestError::Endpoint(GetError::NotFound(_))) => (),
+      _ => panic!("unexpected result: {:?}", result),
+    }
+  }
+}
@@ -0,0 +1,616 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use num_decimal::Num;
+
+use serde::de::Deserializer;
+use serde::de::Error as _;
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::filter::append_filters;
+use crate::api::filter::Filter;
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::api::response::Response;
+use crate::endpoint::finish_query;
+use crate::Error;
+use crate::Str;
+
+
+/// The prefix Polygon uses for option symbols.
+const OPTION_PREFIX: &str = "O:";
+/// The length of the part of an option symbol following the
+/// underlying's symbol, i.e., the expiration date, contract type, and
+/// strike price.
+const SUFFIX_LEN: usize = 15;
+/// The maximum strike price representable in an option symbol, in
+/// thousandths of a dollar.
+const MAX_STRIKE_THOUSANDTHS: u64 = 99_999_999;
+
+
+/// The type of an option contract.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+pub enum ContractType {
+  /// A call option.
+  #[serde(rename = "call")]
+  Call,
+  /// A put option.
+  #[serde(rename = "put")]
+  Put,
+}
+
+impl AsRef<str> for ContractType {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      ContractType::Call => "call",
+      ContractType::Put => "put",
+    }
+  }
+}
+
+
+/// The exercise style of an option contract.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum ExerciseStyle {
+  /// The option can be exercised at any time until expiration.
+  #[serde(rename = "american")]
+  American,
+  /// The option can only be exercised at expiration.
+  #[serde(rename = "european")]
+  European,
+  /// The option can be exercised on a set of predetermined dates.
+  #[serde(rename = "bermudan")]
+  Bermudan,
+}
+
+
+/// An option symbol in the format used by Polygon, e.g.,
+/// `O:AAPL230616C00150000`.
+///
+/// The symbol follows the OCC convention: the underlying's symbol,
+/// the expiration date as `YYMMDD`, `C` or `P` for the contract type,
+/// and the strike price in thousandths of a dollar, padded to eight
+/// digits. Only symbols that can be represented in this format can be
+/// created.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OptionSymbol {
+  /// The symbol of the underlying.
+  underlying: String,
+  /// The expiration date.
+  expiration: NaiveDate,
+  /// The type of the contract.
+  contract_type: ContractType,
+  /// The strike price.
+  strike: Num,
+}
+
+impl OptionSymbol {
+  /// Create a new `OptionSymbol`.
+  ///
+  /// An error is reported if the underlying's symbol is not
+  /// alphanumeric or if the strike price is not positive, exceeds
+  /// 99999.999, or has more than three decimal places.
+  #[allow(clippy::result_large_err)]
+  pub fn new<S>(
+    underlying: S,
+    expiration: NaiveDate,
+    contract_type: ContractType,
+    strike: Num,
+  ) -> Result<Self, Error>
+  where
+    S: Into<String>,
+  {
+    let underlying = underlying.into();
+    if underlying.is_empty() || !underlying.bytes().all(|b| b.is_ascii_alphanumeric()) {
+      return Err(Error::Str(
+        format!("invalid option underlying: {:?}", underlying).into(),
+      ))
+    }
+
+    let thousandths = &strike * Num::from(1000);
+    if !strike.is_positive()
+      || !thousandths.fract().is_zero()
+      || thousandths > Num::from(MAX_STRIKE_THOUSANDTHS)
+    {
+      return Err(Error::Str(
+        format!("strike price {} cannot be represented in an option symbol", strike).into(),
+      ))
+    }
+
+    Ok(Self {
+      underlying,
+      expiration,
+      contract_type,
+      strike,
+    })
+  }
+
+  /// Retrieve the symbol of the underlying.
+  #[inline]
+  pub fn underlying(&self) -> &str {
+    &self.underlying
+  }
+
+  /// Retrieve the expiration date.
+  #[inline]
+  pub fn expiration(&self) -> NaiveDate {
+    self.expiration
+  }
+
+  /// Retrieve the type of the contract.
+  #[inline]
+  pub fn contract_type(&self) -> ContractType {
+    self.contract_type
+  }
+
+  /// Retrieve the strike price.
+  #[inline]
+  pub fn strike(&self) -> &Num {
+    &self.strike
+  }
+}
+
+impl Display for OptionSymbol {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    let contract_type = match self.contract_type {
+      ContractType::Call => 'C',
+      ContractType::Put => 'P',
+    };
+    // The strike price got validated on construction, so the
+    // conversion is exact and fits into eight digits.
+    let strike = (&self.strike * Num::from(1000)).to_integer();
+
+    write!(
+      fmt,
+      "{}{}{}{}{:08}",
+      OPTION_PREFIX,
+      self.underlying,
+      self.expiration.format("%y%m%d"),
+      contract_type,
+      strike
+    )
+  }
+}
+
+impl FromStr for OptionSymbol {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || Error::Str(format!("invalid option symbol: {}", s).into());
+
+    let symbol = s.strip_prefix(OPTION_PREFIX).ok_or_else(invalid)?;
+    if symbol.len() <= SUFFIX_LEN || !symbol.is_ascii() {
+      return Err(invalid())
+    }
+
+    let (underlying, suffix) = symbol.split_at(symbol.len() - SUFFIX_LEN);
+    let expiration =
+      NaiveDate::parse_from_str(&suffix[..6], "%y%m%d").map_err(|_| invalid())?;
+    let contract_type = match &suffix[6..7] {
+      "C" => ContractType::Call,
+      "P" => ContractType::Put,
+      _ => return Err(invalid()),
+    };
+    let strike = &suffix[7..];
+    if !strike.bytes().all(|b| b.is_ascii_digit()) {
+      return Err(invalid())
+    }
+    let strike = u64::from_str(strike).map_err(|_| invalid())?;
+
+    Self::new(underlying, expiration, contract_type, Num::new(strike, 1000))
+      .map_err(|_| invalid())
+  }
+}
+
+impl<'de> Deserialize<'de> for OptionSymbol {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let symbol = String::deserialize(deserializer)?;
+    Self::from_str(&symbol).map_err(D::Error::custom)
+  }
+}
+
+
+/// The field to sort option contracts by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the option symbol.
+  Ticker,
+  /// Sort by the underlying's symbol.
+  UnderlyingTicker,
+  /// Sort by the expiration date.
+  ExpirationDate,
+  /// Sort by the strike price.
+  StrikePrice,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Ticker => "ticker",
+      Sort::UnderlyingTicker => "underlying_ticker",
+      Sort::ExpirationDate => "expiration_date",
+      Sort::StrikePrice => "strike_price",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v3/reference/options/contracts`
+/// endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContractReq {
+  /// Only report contracts on this underlying.
+  pub underlying: Option<String>,
+  /// Only report contracts of this type.
+  pub contract_type: Option<ContractType>,
+  /// Filters on the expiration date.
+  pub expiration_date: Vec<Filter<NaiveDate>>,
+  /// Filters on the strike price.
+  pub strike_price: Vec<Filter<Num>>,
+  /// Report contracts as they were on this date.
+  pub as_of: Option<NaiveDate>,
+  /// Whether to report expired contracts instead of active ones.
+  pub expired: Option<bool>,
+  /// The order in which to report contracts.
+  pub order: Option<Order>,
+  /// The field to sort contracts by.
+  pub sort: Option<Sort>,
+  /// The maximum number of contracts to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// An option contract as returned by the
+/// `/v3/reference/options/contracts` endpoint.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Contract {
+  /// The contract's symbol.
+  #[serde(rename = "ticker")]
+  pub symbol: OptionSymbol,
+  /// The symbol of the underlying.
+  #[serde(rename = "underlying_ticker")]
+  pub underlying: String,
+  /// The type of the contract.
+  #[serde(rename = "contract_type")]
+  pub contract_type: ContractType,
+  /// The exercise style of the contract.
+  #[serde(rename = "exercise_style")]
+  pub exercise_style: Option<ExerciseStyle>,
+  /// The expiration date.
+  #[serde(rename = "expiration_date")]
+  pub expiration_date: NaiveDate,
+  /// The strike price.
+  #[serde(rename = "strike_price")]
+  pub strike_price: Num,
+  /// The number of shares of the underlying a contract covers.
+  #[serde(rename = "shares_per_contract")]
+  pub shares_per_contract: Option<u64>,
+  /// The MIC of the primary exchange the contract trades on.
+  #[serde(rename = "primary_exchange")]
+  pub primary_exchange: Option<String>,
+  /// The contract's six letter CFI code.
+  #[serde(rename = "cfi")]
+  pub cfi: Option<String>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/reference/options/contracts` endpoint.
+  pub List(ContractReq),
+  Ok => Page<Contract>, [
+    /// The option contracts were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v3/reference/options/contracts".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      if let Some(underlying) = &input.underlying {
+        query.append_pair("underlying_ticker", underlying);
+      }
+      if let Some(contract_type) = &input.contract_type {
+        query.append_pair("contract_type", contract_type.as_ref());
+      }
+      append_filters(&mut query, "expiration_date", &input.expiration_date);
+      append_filters(&mut query, "strike_price", &input.strike_price);
+      if let Some(as_of) = &input.as_of {
+        query.append_pair("as_of", &as_of.format("%Y-%m-%d").to_string());
+      }
+      if let Some(expired) = &input.expired {
+        query.append_pair("expired", &expired.to_string());
+      }
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Contract;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/reference/options/contracts/<symbol>` endpoint.
+  pub Get(OptionSymbol),
+  Ok => Response<Contract>, [
+    /// The option contract was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The option contract was not found.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v3/reference/options/contracts/{}", input).into()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::StreamExt as _;
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we can parse and format option symbols.
+  #[test]
+  fn option_symbol_round_trip() {
+    let symbol = OptionSymbol::from_str("O:AAPL230616C00150000").unwrap();
+    assert_eq!(symbol.underlying(), "AAPL");
+    assert_eq!(
+      symbol.expiration(),
+      NaiveDate::from_str("2023-06-16").unwrap()
+    );
+    assert_eq!(symbol.contract_type(), ContractType::Call);
+    assert_eq!(symbol.strike(), &Num::from(150));
+    assert_eq!(symbol.to_string(), "O:AAPL230616C00150000");
+
+    let symbol = OptionSymbol::from_str("O:SPY1221230P00372500").unwrap();
+    assert_eq!(symbol.underlying(), "SPY1");
+    assert_eq!(symbol.contract_type(), ContractType::Put);
+    assert_eq!(symbol.strike(), &Num::new(3725, 10));
+    assert_eq!(symbol.to_string(), "O:SPY1221230P00372500");
+
+    let expiration = NaiveDate::from_str("2024-01-19").unwrap();
+    let symbol = OptionSymbol::new("F", expiration, ContractType::Put, Num::new(25, 2)).unwrap();
+    assert_eq!(symbol.to_string(), "O:F240119P00012500");
+
+    let strike = Num::new(99_999_999, 1000);
+    let symbol = OptionSymbol::new("BRKA", expiration, ContractType::Call, strike).unwrap();
+    assert_eq!(symbol.to_string(), "O:BRKA240119C99999999");
+
+    let symbol = OptionSymbol::new("F", expiration, ContractType::Call, Num::new(1, 1000)).unwrap();
+    assert_eq!(symbol.to_string(), "O:F240119C00000001");
+    assert_eq!(OptionSymbol::from_str(&symbol.to_string()).unwrap(), symbol);
+  }
+
+  /// Check that we refuse to create option symbols that cannot be
+  /// represented in the OCC format.
+  #[test]
+  fn invalid_option_symbol_parts() {
+    let expiration = NaiveDate::from_str("2024-01-19").unwrap();
+    let valid = |underlying, strike| {
+      OptionSymbol::new(underlying, expiration, ContractType::Call, strike).is_ok()
+    };
+
+    assert!(!valid("F", Num::from(0)));
+    assert!(!valid("F", Num::from(-10)));
+    assert!(!valid("F", Num::from(100_000)));
+    assert!(!valid("F", Num::new(100_000_001, 1000)));
+    assert!(!valid("F", Num::new(1, 10_000)));
+    assert!(!valid("F", Num::new(1_500_005, 10_000)));
+    assert!(!valid("", Num::from(10)));
+    assert!(!valid("BRK B", Num::from(10)));
+    assert!(valid("F", Num::new(150_001, 1000)));
+  }
+
+  /// Check that we reject malformed option symbols.
+  #[test]
+  fn invalid_option_symbols() {
+    let symbols = [
+      "",
+      "AAPL230616C00150000",
+      "O:230616C00150000",
+      "O:AAPL231316C00150000",
+      "O:AAPL230616X00150000",
+      "O:AAPL230616C0015000+",
+      "O:AAPL230616C0015000",
+      "O:AAPL230616C00000000",
+      "O: AAPL230616C00150000",
+    ];
+
+    for symbol in &symbols {
+      assert!(OptionSymbol::from_str(symbol).is_err(), "{}", symbol);
+    }
+  }
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = ContractReq {
+      underlying: Some("AAPL".into()),
+      contract_type: Some(ContractType::Call),
+      expiration_date: vec![
+        Filter::GreaterThanOrEqual(NaiveDate::from_str("2023-06-01").unwrap()),
+        Filter::LessThan(NaiveDate::from_str("2023-07-01").unwrap()),
+      ],
+      strike_price: vec![
+        Filter::GreaterThanOrEqual(Num::from(140)),
+        Filter::LessThanOrEqual(Num::new(3205, 20)),
+      ],
+      expired: Some(true),
+      order: Some(Order::Ascending),
+      sort: Some(Sort::StrikePrice),
+      limit: Some(250),
+      ..Default::default()
+    };
+
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "underlying_ticker=AAPL&contract_type=call&expiration_date.gte=2023-06-01&expiration_date.lt=2023-07-01&strike_price.gte=140&strike_price.lte=160.25&expired=true&order=asc&sort=strike_price&limit=250"
+    );
+
+    let request = ContractReq {
+      cursor: Some("YXA9NTU1NDEyNg".into()),
+      ..request
+    };
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(query, "cursor=YXA9NTU1NDEyNg");
+  }
+
+  /// Check that we use the expected path for a single contract.
+  #[test]
+  fn encode_get_path() {
+    let symbol = OptionSymbol::from_str("O:AAPL230616C00150000").unwrap();
+    assert_eq!(
+      Get::path(&symbol),
+      "/v3/reference/options/contracts/O:AAPL230616C00150000"
+    );
+  }
+
+  /// Make sure that we can deserialize a page of option contracts.
+  #[test]
+  fn deserialize_contracts() {
+    let response = r#"{
+  "next_url": "https://api.polygon.io/v3/reference/options/contracts?cursor=YXA9MSZhcz0mbGltaXQ9MQ",
+  "request_id": "603902c0-a5a5-406f-bd08-f030f92418fa",
+  "results": [
+    {
+      "cfi": "OCASPS",
+      "contract_type": "call",
+      "exercise_style": "american",
+      "expiration_date": "2023-06-16",
+      "primary_exchange": "BATO",
+      "shares_per_contract": 100,
+      "strike_price": 150,
+      "ticker": "O:AAPL230616C00150000",
+      "underlying_ticker": "AAPL"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Contract>>(response).unwrap();
+    assert!(page.cursor.is_some());
+    assert_eq!(page.results.len(), 1);
+
+    let contract = &page.results[0];
+    assert_eq!(contract.symbol.to_string(), "O:AAPL230616C00150000");
+    assert_eq!(contract.symbol.strike(), &contract.strike_price);
+    assert_eq!(contract.underlying, "AAPL");
+    assert_eq!(contract.contract_type, ContractType::Call);
+    assert_eq!(contract.exercise_style, Some(ExerciseStyle::American));
+    assert_eq!(
+      contract.expiration_date,
+      NaiveDate::from_str("2023-06-16").unwrap()
+    );
+    assert_eq!(contract.strike_price, Num::from(150));
+    assert_eq!(contract.shares_per_contract, Some(100));
+    assert_eq!(contract.primary_exchange.as_deref(), Some("BATO"));
+    assert_eq!(contract.cfi.as_deref(), Some("OCASPS"));
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_contracts() {
+    let client = Client::from_env().unwrap();
+    let request = ContractReq {
+      underlying: Some("AAPL".into()),
+      contract_type: Some(ContractType::Put),
+      expiration_date: vec![Filter::Equal(NaiveDate::from_str("2023-06-16").unwrap())],
+      strike_price: vec![
+        Filter::GreaterThanOrEqual(Num::from(140)),
+        Filter::LessThanOrEqual(Num::from(160)),
+      ],
+      expired: Some(true),
+      limit: Some(3),
+      ..Default::default()
+    };
+
+    // Use a small page size to exercise pagination, but only retrieve
+    // the first two pages to not run into rate limits.
+    let contracts = client
+      .pages::<List>(request)
+      .take(2)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert!(contracts.len() > 3, "{}", contracts.len());
+    assert!(contracts.len() <= 6, "{}", contracts.len());
+    assert!(contracts.iter().all(|contract| contract.underlying == "AAPL"));
+    assert!(contracts.iter().all(|contract| contract.contract_type == ContractType::Put));
+
+    let symbol = contracts[0].symbol.clone();
+    let contract = client
+      .issue::<Get>(symbol.clone())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+    assert_eq!(contract.symbol, symbol);
+  }
+}
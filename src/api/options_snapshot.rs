@@ -0,0 +1,628 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::serde::ts_nanoseconds_option::deserialize as optional_datetime_from_nanos;
+use chrono::DateTime;
+use chrono::NaiveDate;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::filter::append_filters;
+use crate::api::filter::Filter;
+use crate::api::options::ContractType;
+use crate::api::options::ExerciseStyle;
+use crate::api::options::OptionSymbol;
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::api::response::Response;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// The field to sort option snapshots by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the option symbol.
+  Ticker,
+  /// Sort by the expiration date.
+  ExpirationDate,
+  /// Sort by the strike price.
+  StrikePrice,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Ticker => "ticker",
+      Sort::ExpirationDate => "expiration_date",
+      Sort::StrikePrice => "strike_price",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v3/snapshot/options/<underlying>`
+/// endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainReq {
+  /// The symbol of the underlying to retrieve the option chain for.
+  pub underlying: String,
+  /// Filters on the strike price.
+  pub strike_price: Vec<Filter<Num>>,
+  /// Filters on the expiration date.
+  pub expiration_date: Vec<Filter<NaiveDate>>,
+  /// Only report contracts of this type.
+  pub contract_type: Option<ContractType>,
+  /// The order in which to report snapshots.
+  pub order: Option<Order>,
+  /// The field to sort snapshots by.
+  pub sort: Option<Sort>,
+  /// The maximum number of snapshots to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// A GET request to be made to the
+/// `/v3/snapshot/options/<underlying>/<contract>` endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractReq {
+  /// The symbol of the underlying.
+  ///
+  /// Note that for contracts adjusted for corporate actions this
+  /// symbol may differ from the one embedded in the option symbol.
+  pub underlying: String,
+  /// The symbol of the option contract.
+  pub symbol: OptionSymbol,
+}
+
+
+/// The timeframe of the data in a snapshot.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Timeframe {
+  /// The data is real-time.
+  #[serde(rename = "REAL-TIME")]
+  RealTime,
+  /// The data is delayed.
+  #[serde(rename = "DELAYED")]
+  Delayed,
+}
+
+
+/// The most recent daily bar of an option contract.
+///
+/// All fields may be absent, e.g., if the contract did not trade yet.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Day {
+  /// The open price.
+  #[serde(rename = "open")]
+  pub open_price: Option<Num>,
+  /// The close price.
+  #[serde(rename = "close")]
+  pub close_price: Option<Num>,
+  /// The high price.
+  #[serde(rename = "high")]
+  pub high_price: Option<Num>,
+  /// The low price.
+  #[serde(rename = "low")]
+  pub low_price: Option<Num>,
+  /// The previous day's close price.
+  #[serde(rename = "previous_close")]
+  pub previous_close_price: Option<Num>,
+  /// The change in price since the previous day's close.
+  #[serde(rename = "change")]
+  pub change: Option<Num>,
+  /// The change in price since the previous day's close, in percent.
+  #[serde(rename = "change_percent")]
+  pub change_percent: Option<Num>,
+  /// The trade volume.
+  #[serde(rename = "volume")]
+  pub volume: Option<f64>,
+  /// The volume weighted average price.
+  #[serde(rename = "vwap")]
+  pub volume_weighted_average_price: Option<Num>,
+  /// The time the bar was last updated.
+  #[serde(
+    rename = "last_updated",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub last_updated: Option<DateTime<Utc>>,
+}
+
+
+/// The details of an option contract as part of a `Snapshot`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Details {
+  /// The contract's symbol.
+  #[serde(rename = "ticker")]
+  pub symbol: OptionSymbol,
+  /// The type of the contract.
+  #[serde(rename = "contract_type")]
+  pub contract_type: ContractType,
+  /// The exercise style of the contract.
+  #[serde(rename = "exercise_style")]
+  pub exercise_style: Option<ExerciseStyle>,
+  /// The expiration date.
+  #[serde(rename = "expiration_date")]
+  pub expiration_date: NaiveDate,
+  /// The strike price.
+  #[serde(rename = "strike_price")]
+  pub strike_price: Num,
+  /// The number of shares of the underlying a contract covers.
+  #[serde(rename = "shares_per_contract")]
+  pub shares_per_contract: Option<u64>,
+}
+
+
+/// The greeks of an option contract.
+///
+/// Individual greeks may be absent, e.g., for contracts for which
+/// Polygon has no model.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+pub struct Greeks {
+  /// The change in the option's price per change in the underlying's
+  /// price.
+  #[serde(rename = "delta")]
+  pub delta: Option<f64>,
+  /// The change in delta per change in the underlying's price.
+  #[serde(rename = "gamma")]
+  pub gamma: Option<f64>,
+  /// The change in the option's price per day passing.
+  #[serde(rename = "theta")]
+  pub theta: Option<f64>,
+  /// The change in the option's price per change in implied
+  /// volatility.
+  #[serde(rename = "vega")]
+  pub vega: Option<f64>,
+}
+
+
+/// The most recent quote of an option contract.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct LastQuote {
+  /// The bid price.
+  #[serde(rename = "bid")]
+  pub bid_price: Num,
+  /// The bid quantity, in contracts.
+  #[serde(rename = "bid_size")]
+  pub bid_quantity: u64,
+  /// The ask price.
+  #[serde(rename = "ask")]
+  pub ask_price: Num,
+  /// The ask quantity, in contracts.
+  #[serde(rename = "ask_size")]
+  pub ask_quantity: u64,
+  /// The midpoint between bid and ask price.
+  #[serde(rename = "midpoint")]
+  pub midpoint: Option<Num>,
+  /// The time the quote was last updated.
+  #[serde(
+    rename = "last_updated",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub last_updated: Option<DateTime<Utc>>,
+  /// The timeframe of the quote.
+  #[serde(rename = "timeframe")]
+  pub timeframe: Option<Timeframe>,
+}
+
+
+/// The most recent trade of an option contract.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct LastTrade {
+  /// The conditions of the trade.
+  #[serde(rename = "conditions", default)]
+  pub conditions: Vec<u64>,
+  /// The ID of the exchange the trade occurred on.
+  #[serde(rename = "exchange")]
+  pub exchange: Option<u64>,
+  /// The price.
+  #[serde(rename = "price")]
+  pub price: Num,
+  /// The number of contracts traded.
+  #[serde(rename = "size")]
+  pub quantity: u64,
+  /// The time at which the SIP received the trade.
+  #[serde(
+    rename = "sip_timestamp",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub sip_timestamp: Option<DateTime<Utc>>,
+  /// The timeframe of the trade.
+  #[serde(rename = "timeframe")]
+  pub timeframe: Option<Timeframe>,
+}
+
+
+/// The underlying asset of an option contract.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct UnderlyingAsset {
+  /// The symbol of the underlying.
+  #[serde(rename = "ticker")]
+  pub symbol: String,
+  /// The price of the underlying.
+  #[serde(rename = "price")]
+  pub price: Option<Num>,
+  /// The change in the underlying's price required for the option to
+  /// break even.
+  #[serde(rename = "change_to_break_even")]
+  pub change_to_break_even: Option<Num>,
+  /// The time the data was last updated.
+  #[serde(
+    rename = "last_updated",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub last_updated: Option<DateTime<Utc>>,
+  /// The timeframe of the data.
+  #[serde(rename = "timeframe")]
+  pub timeframe: Option<Timeframe>,
+}
+
+
+/// A snapshot of an option contract as returned by the
+/// `/v3/snapshot/options/<underlying>` endpoint.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Snapshot {
+  /// The contract's details.
+  #[serde(rename = "details")]
+  pub details: Details,
+  /// The most recent daily bar.
+  #[serde(rename = "day", default)]
+  pub day: Day,
+  /// The price the underlying has to reach for the option to break
+  /// even.
+  #[serde(rename = "break_even_price")]
+  pub break_even_price: Option<Num>,
+  /// The contract's greeks.
+  ///
+  /// Greeks are not reported, e.g., for contracts deep in the money.
+  #[serde(rename = "greeks")]
+  pub greeks: Option<Greeks>,
+  /// The implied volatility of the contract.
+  #[serde(rename = "implied_volatility")]
+  pub implied_volatility: Option<f64>,
+  /// The most recent quote.
+  #[serde(rename = "last_quote")]
+  pub last_quote: Option<LastQuote>,
+  /// The most recent trade.
+  #[serde(rename = "last_trade")]
+  pub last_trade: Option<LastTrade>,
+  /// The number of outstanding contracts.
+  #[serde(rename = "open_interest")]
+  pub open_interest: Option<u64>,
+  /// The underlying asset.
+  #[serde(rename = "underlying_asset")]
+  pub underlying_asset: Option<UnderlyingAsset>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/snapshot/options/<underlying>` endpoint.
+  pub List(ChainReq),
+  Ok => Page<Snapshot>, [
+    /// The option chain was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v3/snapshot/options/{}", input.underlying).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      append_filters(&mut query, "strike_price", &input.strike_price);
+      append_filters(&mut query, "expiration_date", &input.expiration_date);
+      if let Some(contract_type) = &input.contract_type {
+        query.append_pair("contract_type", contract_type.as_ref());
+      }
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Snapshot;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/snapshot/options/<underlying>/<contract>` endpoint.
+  pub Get(ContractReq),
+  Ok => Response<Snapshot>, [
+    /// The snapshot was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The option contract was not found.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v3/snapshot/options/{}/{}", input.underlying, input.symbol).into()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::StreamExt as _;
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected paths and query parameters.
+  #[test]
+  fn encode_request() {
+    let request = ChainReq {
+      underlying: "AAPL".into(),
+      strike_price: vec![
+        Filter::GreaterThanOrEqual(Num::from(140)),
+        Filter::LessThan(Num::from(160)),
+      ],
+      expiration_date: vec![Filter::Equal(NaiveDate::from_str("2023-06-16").unwrap())],
+      contract_type: Some(ContractType::Call),
+      order: Some(Order::Descending),
+      sort: Some(Sort::StrikePrice),
+      limit: Some(250),
+      cursor: None,
+    };
+
+    assert_eq!(List::path(&request), "/v3/snapshot/options/AAPL");
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "strike_price.gte=140&strike_price.lt=160&expiration_date=2023-06-16&contract_type=call&order=desc&sort=strike_price&limit=250"
+    );
+
+    let request = ChainReq {
+      cursor: Some("YXA9NTU1NDEyNg".into()),
+      ..request
+    };
+    assert_eq!(List::path(&request), "/v3/snapshot/options/AAPL");
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(query, "cursor=YXA9NTU1NDEyNg");
+
+    let request = ContractReq {
+      underlying: "AAPL".into(),
+      symbol: OptionSymbol::from_str("O:AAPL230616C00150000").unwrap(),
+    };
+    assert_eq!(
+      Get::path(&request),
+      "/v3/snapshot/options/AAPL/O:AAPL230616C00150000"
+    );
+  }
+
+  /// Make sure that we can deserialize a page of option snapshots.
+  #[test]
+  fn deserialize_snapshots() {
+    let response = r#"{
+  "next_url": "https://api.polygon.io/v3/snapshot/options/AAPL?cursor=YXA9MSZhcz0mbGltaXQ9MQ",
+  "request_id": "6a7e466379af0a71039d60cc78e72282",
+  "results": [
+    {
+      "break_even_price": 151.2,
+      "day": {
+        "change": 4.5,
+        "change_percent": 6.76,
+        "close": 1.2,
+        "high": 1.25,
+        "last_updated": 1636520400000000000,
+        "low": 0.9,
+        "open": 0.95,
+        "previous_close": 0.75,
+        "volume": 868,
+        "vwap": 1.05
+      },
+      "details": {
+        "contract_type": "call",
+        "exercise_style": "american",
+        "expiration_date": "2023-06-16",
+        "shares_per_contract": 100,
+        "strike_price": 150,
+        "ticker": "O:AAPL230616C00150000"
+      },
+      "greeks": {
+        "delta": 0.5520187372272933,
+        "gamma": 0.00706756515659829,
+        "theta": -0.018532772783847958,
+        "vega": 0.7274141028010086
+      },
+      "implied_volatility": 0.3048997097864957,
+      "last_quote": {
+        "ask": 1.25,
+        "ask_size": 4,
+        "bid": 1.15,
+        "bid_size": 8,
+        "last_updated": 1636573458756383500,
+        "midpoint": 1.2,
+        "timeframe": "REAL-TIME"
+      },
+      "last_trade": {
+        "conditions": [209],
+        "exchange": 316,
+        "price": 1.2,
+        "sip_timestamp": 1636573458756383500,
+        "size": 2,
+        "timeframe": "REAL-TIME"
+      },
+      "open_interest": 1543,
+      "underlying_asset": {
+        "change_to_break_even": 4.2,
+        "last_updated": 1636573459862384600,
+        "price": 147,
+        "ticker": "AAPL",
+        "timeframe": "DELAYED"
+      }
+    },
+    {
+      "day": {},
+      "details": {
+        "contract_type": "put",
+        "exercise_style": "american",
+        "expiration_date": "2023-06-16",
+        "shares_per_contract": 100,
+        "strike_price": 150,
+        "ticker": "O:AAPL230616P00150000"
+      },
+      "greeks": {},
+      "underlying_asset": {
+        "ticker": "AAPL"
+      }
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Snapshot>>(response).unwrap();
+    assert!(page.cursor.is_some());
+    assert_eq!(page.results.len(), 2);
+
+    let snapshot = &page.results[0];
+    assert_eq!(snapshot.details.symbol.to_string(), "O:AAPL230616C00150000");
+    assert_eq!(snapshot.details.contract_type, ContractType::Call);
+    assert_eq!(snapshot.details.exercise_style, Some(ExerciseStyle::American));
+    assert_eq!(snapshot.details.strike_price, Num::from(150));
+    assert_eq!(snapshot.break_even_price, Some(Num::new(1512, 10)));
+    assert_eq!(snapshot.day.close_price, Some(Num::new(12, 10)));
+    assert_eq!(snapshot.day.previous_close_price, Some(Num::new(75, 100)));
+    assert_eq!(snapshot.day.volume, Some(868f64));
+    assert_eq!(
+      snapshot.day.last_updated,
+      Some(DateTime::parse_from_rfc3339("2021-11-10T05:00:00Z").unwrap().into())
+    );
+
+    let greeks = snapshot.greeks.unwrap();
+    assert!((greeks.delta.unwrap() - 0.552_018_737_227_293_3).abs() < f64::EPSILON);
+    assert!((greeks.gamma.unwrap() - 0.007_067_565_156_598_29).abs() < f64::EPSILON);
+    assert!((greeks.theta.unwrap() + 0.018_532_772_783_847_958).abs() < f64::EPSILON);
+    assert!((greeks.vega.unwrap() - 0.727_414_102_801_008_6).abs() < f64::EPSILON);
+    let volatility = snapshot.implied_volatility.unwrap();
+    assert!((volatility - 0.304_899_709_786_495_7).abs() < f64::EPSILON);
+    assert_eq!(snapshot.open_interest, Some(1543));
+
+    let quote = snapshot.last_quote.as_ref().unwrap();
+    assert_eq!(quote.bid_price, Num::new(115, 100));
+    assert_eq!(quote.ask_quantity, 4);
+    assert_eq!(quote.timeframe, Some(Timeframe::RealTime));
+
+    let trade = snapshot.last_trade.as_ref().unwrap();
+    assert_eq!(trade.conditions, vec![209]);
+    assert_eq!(trade.price, Num::new(12, 10));
+    assert_eq!(trade.quantity, 2);
+
+    let underlying = snapshot.underlying_asset.as_ref().unwrap();
+    assert_eq!(underlying.symbol, "AAPL");
+    assert_eq!(underlying.price, Some(Num::from(147)));
+    assert_eq!(underlying.change_to_break_even, Some(Num::new(42, 10)));
+    assert_eq!(underlying.timeframe, Some(Timeframe::Delayed));
+
+    let snapshot = &page.results[1];
+    assert_eq!(snapshot.details.contract_type, ContractType::Put);
+    assert_eq!(snapshot.day, Day::default());
+    assert_eq!(snapshot.greeks, Some(Greeks::default()));
+    assert_eq!(snapshot.implied_volatility, None);
+    assert_eq!(snapshot.last_quote, None);
+    assert_eq!(snapshot.open_interest, None);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_chain() {
+    let client = Client::from_env().unwrap();
+    let request = ChainReq {
+      underlying: "AAPL".into(),
+      strike_price: vec![
+        Filter::GreaterThanOrEqual(Num::from(140)),
+        Filter::LessThanOrEqual(Num::from(160)),
+      ],
+      contract_type: Some(ContractType::Call),
+      limit: Some(10),
+      ..Default::default()
+    };
+
+    // The chain can span many pages. Only retrieve the first two to
+    // not run into rate limits.
+    let snapshots = client
+      .pages::<List>(request)
+      .take(2)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert!(!snapshots.is_empty());
+    assert!(snapshots.len() <= 20, "{}", snapshots.len());
+    assert!(snapshots.iter().all(|snapshot| {
+      let strike = &snapshot.details.strike_price;
+      snapshot.details.contract_type == ContractType::Call
+        && *strike >= Num::from(140)
+        && *strike <= Num::from(160)
+    }));
+
+    let request = ContractReq {
+      underlying: "AAPL".into(),
+      symbol: snapshots[0].details.symbol.clone(),
+    };
+    let snapshot = client
+      .issue::<Get>(request.clone())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+    assert_eq!(snapshot.details.symbol, request.symbol);
+  }
+}
@@ -0,0 +1,115 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use http_endpoint::Endpoint;
+
+use serde::de::Deserializer;
+use serde::de::Error;
+use serde::de::Unexpected;
+use serde::Deserialize;
+
+use url::Url;
+
+
+/// The query parameter Polygon uses for communicating the cursor.
+const CURSOR_PARAM: &str = "cursor";
+
+
+/// Extract the pagination cursor from a `next_url`.
+fn cursor_from_url<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let next_url = Option::<String>::deserialize(deserializer)?;
+  match next_url {
+    Some(next_url) => {
+      let url = Url::parse(&next_url)
+        .map_err(|_| Error::invalid_value(Unexpected::Str(&next_url), &"a URL"))?;
+      let cursor = url
+        .query_pairs()
+        .find(|(key, _)| key == CURSOR_PARAM)
+        .map(|(_, value)| value.into_owned())
+        .ok_or_else(|| Error::invalid_value(Unexpected::Str(&next_url), &"a URL with a cursor"))?;
+      Ok(Some(cursor))
+    },
+    None => Ok(None),
+  }
+}
+
+
+/// The order in which results are reported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Order {
+  /// Report results in ascending order.
+  Ascending,
+  /// Report results in descending order.
+  Descending,
+}
+
+impl AsRef<str> for Order {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Order::Ascending => "asc",
+      Order::Descending => "desc",
+    }
+  }
+}
+
+
+/// A single page of results as reported by endpoints supporting
+/// pagination.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Page<T> {
+  /// The results contained in this page.
+  #[serde(rename = "results", default = "Vec::new")]
+  pub results: Vec<T>,
+  /// The cursor to use for retrieving the next page, if any.
+  #[serde(rename = "next_url", default, deserialize_with = "cursor_from_url")]
+  pub cursor: Option<String>,
+}
+
+
+/// A trait for endpoints reporting their results in pages.
+///
+/// Endpoints implementing this trait can be used with
+/// `Client::pages` to retrieve all pages transparently.
+pub trait Paginated: Endpoint<Output = Page<<Self as Paginated>::Item>> {
+  /// The type of the items reported.
+  type Item;
+
+  /// Set the cursor to use for the request, instructing it to retrieve
+  /// the page it refers to.
+  fn set_cursor(input: &mut Self::Input, cursor: String);
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use serde_json::from_str as from_json;
+
+
+  /// Check that we can decode a page and extract its cursor.
+  #[test]
+  fn decode_page() {
+    let json = r#"{
+  "next_url": "https://api.polygon.io/v3/trades/AAPL?cursor=YXA9NTU1NDEyNg",
+  "request_id": "a47d1beb8c11b6ae897ab76cdbbf35a3",
+  "results": [1, 2, 3],
+  "status": "OK"
+}"#;
+    let page = from_json::<Page<u64>>(json).unwrap();
+    assert_eq!(page.results, vec![1, 2, 3]);
+    assert_eq!(page.cursor.as_deref(), Some("YXA9NTU1NDEyNg"));
+  }
+
+  /// Check that we can decode the last page, which lacks a cursor.
+  #[test]
+  fn decode_last_page() {
+    let json = r#"{"request_id":"6a7e466379af0a71039d60cc78e72282","status":"OK"}"#;
+    let page = from_json::<Page<u64>>(json).unwrap();
+    assert_eq!(page.results, Vec::<u64>::new());
+    assert_eq!(page.cursor, None);
+  }
+}
@@ -0,0 +1,280 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::serde::ts_nanoseconds::deserialize as datetime_from_nanos;
+use chrono::serde::ts_nanoseconds_option::deserialize as optional_datetime_from_nanos;
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::api::trades::append_timestamp_filters;
+use crate::api::Filter;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+pub use crate::api::trades::Sort;
+
+
+/// A GET request to be made to the `/v3/quotes/<symbol>` endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QuoteReq {
+  /// The ticker symbol to request quotes for.
+  pub symbol: String,
+  /// Filters on the quotes' SIP timestamp.
+  pub timestamp: Vec<Filter<DateTime<Utc>>>,
+  /// The order in which to report quotes.
+  pub order: Option<Order>,
+  /// The field to sort quotes by.
+  pub sort: Option<Sort>,
+  /// The maximum number of quotes to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// A national best bid and offer (NBBO) quote as returned by the
+/// `/v3/quotes/<symbol>` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Quote {
+  /// The ID of the exchange providing the ask.
+  #[serde(rename = "ask_exchange")]
+  pub ask_exchange: Option<u64>,
+  /// The ask price.
+  #[serde(rename = "ask_price")]
+  pub ask_price: Num,
+  /// The ask size in round lots.
+  #[serde(rename = "ask_size")]
+  pub ask_size: u64,
+  /// The ID of the exchange providing the bid.
+  #[serde(rename = "bid_exchange")]
+  pub bid_exchange: Option<u64>,
+  /// The bid price.
+  #[serde(rename = "bid_price")]
+  pub bid_price: Num,
+  /// The bid size in round lots.
+  #[serde(rename = "bid_size")]
+  pub bid_size: u64,
+  /// The conditions of the quote.
+  #[serde(rename = "conditions", default)]
+  pub conditions: Vec<u64>,
+  /// The indicators of the quote.
+  #[serde(rename = "indicators", default)]
+  pub indicators: Vec<u64>,
+  /// The time at which the quote was generated at the exchange.
+  #[serde(
+    rename = "participant_timestamp",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub participant_timestamp: Option<DateTime<Utc>>,
+  /// The sequence number, unique per ticker and day.
+  ///
+  /// Sequence numbers increase but are not necessarily consecutive.
+  #[serde(rename = "sequence_number")]
+  pub sequence_number: u64,
+  /// The time at which the SIP received the quote.
+  #[serde(rename = "sip_timestamp", deserialize_with = "datetime_from_nanos")]
+  pub sip_timestamp: DateTime<Utc>,
+  /// The tape the quote was reported to.
+  ///
+  /// `1` is tape A (NYSE), `2` is tape B (NYSE Arca, NYSE American,
+  /// and regional exchanges), and `3` is tape C (Nasdaq).
+  #[serde(rename = "tape")]
+  pub tape: Option<u8>,
+  /// The time at which the trade reporting facility received the
+  /// quote.
+  #[serde(
+    rename = "trf_timestamp",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub trf_timestamp: Option<DateTime<Utc>>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the `/v3/quotes/<symbol>`
+  /// endpoint.
+  pub Get(QuoteReq),
+  Ok => Page<Quote>, [
+    /// The quotes were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v3/quotes/{}", input.symbol).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      append_timestamp_filters(&mut query, &input.timestamp);
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for Get {
+  type Item = Quote;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::StreamExt as _;
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = QuoteReq {
+      symbol: "SPY".into(),
+      timestamp: vec![Filter::GreaterThanOrEqual(
+        DateTime::parse_from_rfc3339("2021-12-01T10:00:00-05:00").unwrap().into(),
+      )],
+      sort: Some(Sort::Timestamp),
+      limit: Some(10),
+      ..Default::default()
+    };
+
+    let query = Get::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "timestamp.gte=1638370800000000000&sort=timestamp&limit=10"
+    );
+
+    let request = QuoteReq {
+      symbol: "SPY".into(),
+      ..Default::default()
+    };
+    assert_eq!(Get::query(&request).unwrap(), None);
+  }
+
+  /// Make sure that we can deserialize a page of quotes.
+  #[test]
+  fn deserialize_quotes() {
+    let response = r#"{
+  "next_url": "https://api.polygon.io/v3/quotes/AAPL?cursor=YWN0aXZlPXRydWUmZGF0ZT0yMDIxLTA0LTI1",
+  "request_id": "a47d1beb8c11b6ae897ab76cdbbf35a3",
+  "results": [
+    {
+      "ask_exchange": 0,
+      "ask_price": 0,
+      "ask_size": 0,
+      "bid_exchange": 11,
+      "bid_price": 102.7,
+      "bid_size": 60,
+      "conditions": [1],
+      "participant_timestamp": 1517562000065321200,
+      "sequence_number": 2060,
+      "sip_timestamp": 1517562000065700400,
+      "tape": 3
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Quote>>(response).unwrap();
+    assert!(page.cursor.is_some());
+    assert_eq!(page.results.len(), 1);
+
+    let quote = &page.results[0];
+    assert_eq!(quote.ask_exchange, Some(0));
+    assert_eq!(quote.ask_price, Num::from(0));
+    assert_eq!(quote.ask_size, 0);
+    assert_eq!(quote.bid_exchange, Some(11));
+    assert_eq!(quote.bid_price, Num::new(1027, 10));
+    assert_eq!(quote.bid_size, 60);
+    assert_eq!(quote.conditions, vec![1]);
+    assert_eq!(quote.indicators, Vec::<u64>::new());
+    assert_eq!(quote.sequence_number, 2060);
+    assert_eq!(
+      quote.sip_timestamp,
+      DateTime::parse_from_rfc3339("2018-02-02T09:00:00.0657004Z").unwrap()
+    );
+    assert_eq!(quote.tape, Some(3));
+    assert_eq!(quote.trf_timestamp, None);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_spy_quotes() {
+    let client = Client::from_env().unwrap();
+    let start = DateTime::parse_from_rfc3339("2021-12-01T10:00:00-05:00").unwrap();
+    let end = DateTime::parse_from_rfc3339("2021-12-01T10:00:01-05:00").unwrap();
+    let request = QuoteReq {
+      symbol: "SPY".into(),
+      timestamp: vec![
+        Filter::GreaterThanOrEqual(start.into()),
+        Filter::LessThan(end.into()),
+      ],
+      order: Some(Order::Ascending),
+      sort: Some(Sort::Timestamp),
+      limit: Some(100),
+      cursor: None,
+    };
+
+    // There are thousands of quotes in the time span. Only retrieve the
+    // first two pages to not run into rate limits.
+    let quotes = client
+      .pages::<Get>(request)
+      .take(2)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert!(!quotes.is_empty());
+    assert!(quotes.len() <= 200);
+    assert!(quotes.iter().all(|quote| quote.sip_timestamp >= start));
+    assert!(quotes.iter().all(|quote| quote.sip_timestamp < end));
+    assert!(quotes.iter().all(|quote| quote.bid_price <= quote.ask_price));
+  }
+}
@@ -0,0 +1,446 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::serde::ts_milliseconds_option::deserialize as optional_datetime_from_millis;
+use chrono::serde::ts_nanoseconds::deserialize as datetime_from_nanos;
+use chrono::serde::ts_nanoseconds_option::deserialize as optional_datetime_from_nanos;
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// The direction of the price movement to retrieve snapshots for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+  /// The tickers with the largest gains since the previous day's
+  /// close.
+  Gainers,
+  /// The tickers with the largest losses since the previous day's
+  /// close.
+  Losers,
+}
+
+impl AsRef<str> for Direction {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Direction::Gainers => "gainers",
+      Direction::Losers => "losers",
+    }
+  }
+}
+
+
+/// A GET request to be made to the
+/// `/v2/snapshot/locale/us/markets/stocks/<direction>` endpoint.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoversReq {
+  /// The direction of the price movement.
+  pub direction: Direction,
+  /// Whether to include OTC securities.
+  pub include_otc: bool,
+}
+
+
+/// A bar (open, high, low, close, and volume) as part of a
+/// `Snapshot`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Bar {
+  /// The open price.
+  #[serde(rename = "o")]
+  pub open_price: Num,
+  /// The close price.
+  #[serde(rename = "c")]
+  pub close_price: Num,
+  /// The high price.
+  #[serde(rename = "h")]
+  pub high_price: Num,
+  /// The low price.
+  #[serde(rename = "l")]
+  pub low_price: Num,
+  /// The trade volume.
+  ///
+  /// This field's type is float because Polygon may use exponential
+  /// format for the number.
+  #[serde(rename = "v")]
+  pub volume: f64,
+  /// The volume weighted average price.
+  #[serde(rename = "vw")]
+  pub volume_weighted_average_price: Option<Num>,
+}
+
+
+/// The most recent minute bar as part of a `Snapshot`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct MinuteBar {
+  /// The bar's start timestamp.
+  #[serde(rename = "t", default, deserialize_with = "optional_datetime_from_millis")]
+  pub timestamp: Option<DateTime<Utc>>,
+  /// The open price.
+  #[serde(rename = "o")]
+  pub open_price: Num,
+  /// The close price.
+  #[serde(rename = "c")]
+  pub close_price: Num,
+  /// The high price.
+  #[serde(rename = "h")]
+  pub high_price: Num,
+  /// The low price.
+  #[serde(rename = "l")]
+  pub low_price: Num,
+  /// The trade volume during the minute.
+  #[serde(rename = "v")]
+  pub volume: f64,
+  /// The accumulated volume of the day.
+  #[serde(rename = "av")]
+  pub accumulated_volume: f64,
+  /// The volume weighted average price.
+  #[serde(rename = "vw")]
+  pub volume_weighted_average_price: Option<Num>,
+  /// The number of trades in the bar.
+  #[serde(rename = "n")]
+  pub trade_count: Option<u64>,
+}
+
+
+/// The most recent trade as part of a `Snapshot`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct LastTrade {
+  /// The conditions of the trade.
+  #[serde(rename = "c", default)]
+  pub conditions: Vec<u64>,
+  /// The trade ID.
+  #[serde(rename = "i")]
+  pub id: String,
+  /// The price.
+  #[serde(rename = "p")]
+  pub price: Num,
+  /// The number of shares traded.
+  #[serde(rename = "s")]
+  pub quantity: u64,
+  /// The trade's timestamp.
+  #[serde(rename = "t", deserialize_with = "datetime_from_nanos")]
+  pub timestamp: DateTime<Utc>,
+  /// The exchange the trade occurred on.
+  #[serde(rename = "x")]
+  pub exchange: u64,
+}
+
+
+/// The most recent NBBO quote as part of a `Snapshot`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct LastQuote {
+  /// The bid price.
+  #[serde(rename = "p")]
+  pub bid_price: Num,
+  /// The bid quantity, in round lots.
+  #[serde(rename = "s")]
+  pub bid_quantity: u64,
+  /// The ask price.
+  #[serde(rename = "P")]
+  pub ask_price: Num,
+  /// The ask quantity, in round lots.
+  #[serde(rename = "S")]
+  pub ask_quantity: u64,
+  /// The quote's timestamp.
+  #[serde(rename = "t", deserialize_with = "datetime_from_nanos")]
+  pub timestamp: DateTime<Utc>,
+}
+
+
+/// A snapshot of a ticker's most recent market data.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Snapshot {
+  /// The ticker's symbol.
+  #[serde(rename = "ticker")]
+  pub symbol: String,
+  /// The bar for the current day.
+  #[serde(rename = "day")]
+  pub day: Option<Bar>,
+  /// The bar for the previous trading day.
+  #[serde(rename = "prevDay")]
+  pub prev_day: Option<Bar>,
+  /// The most recent minute bar.
+  #[serde(rename = "min")]
+  pub minute: Option<MinuteBar>,
+  /// The most recent trade.
+  #[serde(rename = "lastTrade")]
+  pub last_trade: Option<LastTrade>,
+  /// The most recent NBBO quote.
+  #[serde(rename = "lastQuote")]
+  pub last_quote: Option<LastQuote>,
+  /// The change in price today, relative to the previous day's close.
+  #[serde(rename = "todaysChange")]
+  pub todays_change: Num,
+  /// The change in price today, in percent.
+  #[serde(rename = "todaysChangePerc")]
+  pub todays_change_percent: Num,
+  /// The time at which the snapshot was last updated.
+  #[serde(rename = "updated", default, deserialize_with = "optional_datetime_from_nanos")]
+  pub updated: Option<DateTime<Utc>>,
+}
+
+
+/// A snapshot as returned by the
+/// `/v2/snapshot/locale/us/markets/stocks/tickers/<symbol>` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SnapshotResp {
+  /// The snapshot.
+  #[serde(rename = "ticker")]
+  pub snapshot: Snapshot,
+}
+
+
+/// A list of snapshots as returned by the
+/// `/v2/snapshot/locale/us/markets/stocks/tickers` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct SnapshotsResp {
+  /// The snapshots.
+  #[serde(rename = "tickers", default)]
+  pub snapshots: Vec<Snapshot>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v2/snapshot/locale/us/markets/stocks/tickers/<symbol>` endpoint.
+  pub Get(String),
+  Ok => SnapshotResp, [
+    /// The snapshot was retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// No snapshot was found for the given symbol.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v2/snapshot/locale/us/markets/stocks/tickers/{}", input).into()
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v2/snapshot/locale/us/markets/stocks/tickers` endpoint.
+  ///
+  /// The input is the list of symbols to retrieve snapshots for. If it
+  /// is empty, snapshots for the entire market are retrieved.
+  pub List(Vec<String>),
+  Ok => SnapshotsResp, [
+    /// The snapshots were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, []
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v2/snapshot/locale/us/markets/stocks/tickers".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if !input.is_empty() {
+      query.append_pair("tickers", &input.join(","));
+    }
+    Ok(finish_query(query))
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v2/snapshot/locale/us/markets/stocks/<direction>` endpoint.
+  pub Movers(MoversReq),
+  Ok => SnapshotsResp, [
+    /// The snapshots were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => MoversError, []
+
+  fn path(input: &Self::Input) -> Str {
+    format!(
+      "/v2/snapshot/locale/us/markets/stocks/{}",
+      input.direction.as_ref()
+    )
+    .into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if input.include_otc {
+      query.append_pair("include_otc", "true");
+    }
+    Ok(finish_query(query))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    assert_eq!(List::query(&Vec::new()).unwrap(), None);
+
+    let symbols = vec!["AAPL".to_string(), "MSFT".to_string()];
+    let query = List::query(&symbols).unwrap().unwrap();
+    assert_eq!(query, "tickers=AAPL%2CMSFT");
+
+    let request = MoversReq {
+      direction: Direction::Losers,
+      include_otc: false,
+    };
+    assert_eq!(Movers::path(&request), "/v2/snapshot/locale/us/markets/stocks/losers");
+    assert_eq!(Movers::query(&request).unwrap(), None);
+
+    let request = MoversReq {
+      direction: Direction::Gainers,
+      include_otc: true,
+    };
+    assert_eq!(Movers::path(&request), "/v2/snapshot/locale/us/markets/stocks/gainers");
+    assert_eq!(Movers::query(&request).unwrap().unwrap(), "include_otc=true");
+  }
+
+  /// Make sure that we can deserialize a snapshot.
+  #[test]
+  fn deserialize_snapshot() {
+    let response = r#"{
+  "request_id": "657e430f1ae768891f018e08e03598d8",
+  "status": "OK",
+  "ticker": {
+    "day": {"c": 120.4229, "h": 120.53, "l": 118.81, "o": 119.62, "v": 28727868, "vw": 119.725},
+    "lastQuote": {"P": 120.47, "S": 4, "p": 120.46, "s": 8, "t": 1605195918507251700},
+    "lastTrade": {"c": [14, 41], "i": "4046", "p": 120.47, "s": 236, "t": 1605195918306274000, "x": 10},
+    "min": {"av": 28724441, "c": 120.4201, "h": 120.468, "l": 120.37, "n": 762, "o": 120.435, "t": 1684428720000, "v": 270796, "vw": 120.4129},
+    "prevDay": {"c": 119.49, "h": 119.63, "l": 116.44, "o": 117.19, "v": 110597265, "vw": 118.4998},
+    "ticker": "AAPL",
+    "todaysChange": 0.98,
+    "todaysChangePerc": 0.82,
+    "updated": 1605195918306274000
+  }
+}"#;
+
+    let snapshot = from_json::<SnapshotResp>(response).unwrap().snapshot;
+    assert_eq!(snapshot.symbol, "AAPL");
+
+    let day = snapshot.day.unwrap();
+    assert_eq!(day.open_price, Num::new(11962, 100));
+    assert_eq!(day.close_price, Num::new(1_204_229, 10000));
+    assert!((day.volume - 28_727_868f64).abs() <= f64::EPSILON, "{}", day.volume);
+
+    let prev_day = snapshot.prev_day.unwrap();
+    assert_eq!(prev_day.close_price, Num::new(11949, 100));
+
+    let minute = snapshot.minute.unwrap();
+    assert_eq!(minute.trade_count, Some(762));
+    assert_eq!(
+      minute.timestamp,
+      Some(DateTime::parse_from_rfc3339("2023-05-18T16:52:00Z").unwrap().into())
+    );
+
+    let trade = snapshot.last_trade.unwrap();
+    assert_eq!(trade.conditions, vec![14, 41]);
+    assert_eq!(trade.price, Num::new(12047, 100));
+    assert_eq!(trade.quantity, 236);
+    assert_eq!(trade.exchange, 10);
+
+    let quote = snapshot.last_quote.unwrap();
+    assert_eq!(quote.bid_price, Num::new(12046, 100));
+    assert_eq!(quote.ask_price, Num::new(12047, 100));
+    assert_eq!(quote.bid_quantity, 8);
+    assert_eq!(quote.ask_quantity, 4);
+
+    assert_eq!(snapshot.todays_change, Num::new(98, 100));
+    assert_eq!(snapshot.todays_change_percent, Num::new(82, 100));
+    assert_eq!(
+      snapshot.updated,
+      Some(DateTime::parse_from_rfc3339("2020-11-12T15:45:18.306274Z").unwrap().into())
+    );
+  }
+
+  /// Check that we can deserialize a list of snapshots.
+  #[test]
+  fn deserialize_snapshots() {
+    let response = r#"{
+  "count": 1,
+  "status": "OK",
+  "tickers": [
+    {
+      "day": {"c": 20.506, "h": 20.64, "l": 20.506, "o": 20.64, "v": 37216, "vw": 20.616},
+      "prevDay": {"c": 20.63, "h": 21, "l": 20.5, "o": 20.79, "v": 292738, "vw": 20.6939},
+      "ticker": "BCDA",
+      "todaysChange": -0.124,
+      "todaysChangePerc": -0.601,
+      "updated": 1605192894630916600
+    }
+  ]
+}"#;
+
+    let snapshots = from_json::<SnapshotsResp>(response).unwrap().snapshots;
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].symbol, "BCDA");
+    assert_eq!(snapshots[0].minute, None);
+    assert_eq!(snapshots[0].last_trade, None);
+    assert_eq!(snapshots[0].todays_change, Num::new(-124, 1000));
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_snapshot() {
+    let client = Client::from_env().unwrap();
+    let snapshot = client
+      .issue::<Get>("AAPL".into())
+      .await
+      .unwrap()
+      .snapshot;
+
+    assert_eq!(snapshot.symbol, "AAPL");
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_snapshots() {
+    let client = Client::from_env().unwrap();
+    let symbols = vec!["AAPL".to_string(), "SPY".to_string()];
+    let snapshots = client.issue::<List>(symbols).await.unwrap().snapshots;
+
+    assert_eq!(snapshots.len(), 2);
+    assert!(snapshots.iter().any(|snapshot| snapshot.symbol == "AAPL"));
+    assert!(snapshots.iter().any(|snapshot| snapshot.symbol == "SPY"));
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_gainers() {
+    let client = Client::from_env().unwrap();
+    let request = MoversReq {
+      direction: Direction::Gainers,
+      include_otc: false,
+    };
+    let snapshots = client.issue::<Movers>(request).await.unwrap().snapshots;
+
+    assert!(snapshots
+      .iter()
+      .all(|snapshot| snapshot.todays_change_percent >= Num::from(0)));
+  }
+}
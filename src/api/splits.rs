@@ -0,0 +1,495 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::NaiveDate;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::aggregates::Aggregate;
+use crate::api::filter::append_filters;
+use crate::api::filter::Filter;
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::endpoint::finish_query;
+use crate::Error;
+use crate::Str;
+
+
+/// The field to sort splits by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the ticker symbol.
+  Ticker,
+  /// Sort by the execution date.
+  ExecutionDate,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Ticker => "ticker",
+      Sort::ExecutionDate => "execution_date",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v3/reference/splits` endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SplitReq {
+  /// Only report splits of this ticker.
+  pub ticker: Option<String>,
+  /// Filters on the execution date.
+  pub execution_date: Vec<Filter<NaiveDate>>,
+  /// Only report reverse splits (`true`) or forward splits (`false`).
+  pub reverse_split: Option<bool>,
+  /// The order in which to report splits.
+  pub order: Option<Order>,
+  /// The field to sort splits by.
+  pub sort: Option<Sort>,
+  /// The maximum number of splits to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// A stock split as returned by the `/v3/reference/splits` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Split {
+  /// The split's ID.
+  #[serde(rename = "id")]
+  pub id: Option<String>,
+  /// The ticker symbol.
+  #[serde(rename = "ticker")]
+  pub ticker: String,
+  /// The date on which the split takes effect.
+  #[serde(rename = "execution_date")]
+  pub execution_date: NaiveDate,
+  /// The number of shares before the split.
+  #[serde(rename = "split_from")]
+  pub split_from: Num,
+  /// The number of shares after the split.
+  #[serde(rename = "split_to")]
+  pub split_to: Num,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/reference/splits` endpoint.
+  pub List(SplitReq),
+  Ok => Page<Split>, [
+    /// The splits were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v3/reference/splits".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      if let Some(ticker) = &input.ticker {
+        query.append_pair("ticker", ticker);
+      }
+      append_filters(&mut query, "execution_date", &input.execution_date);
+      if let Some(reverse_split) = &input.reverse_split {
+        query.append_pair("reverse_split", &reverse_split.to_string());
+      }
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Split;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+/// Determine the trading date an aggregate belongs to.
+fn trading_date(timestamp: &DateTime<Utc>) -> NaiveDate {
+  // US exchanges trade between 04:00 and 20:00 Eastern time. Shifting
+  // by the daylight saving time offset of four hours maps all of these
+  // times, as well as daily aggregates stamped at midnight Eastern
+  // time, onto the correct date, regardless of whether daylight saving
+  // time is in effect or not.
+  (*timestamp - Duration::hours(4)).naive_utc().date()
+}
+
+
+/// Calculate the factor by which the number of shares of the given
+/// ticker changed due to the splits that happened after the given date.
+#[allow(clippy::result_large_err)]
+fn share_factor(ticker: &str, date: NaiveDate, splits: &[Split]) -> Result<Num, Error> {
+  splits
+    .iter()
+    .filter(|split| split.ticker == ticker && split.execution_date > date)
+    .try_fold(Num::from(1), |factor, split| {
+      if !split.split_from.is_positive() || !split.split_to.is_positive() {
+        return Err(Error::Str(
+          format!(
+            "split of {} on {} has invalid ratio {}:{}",
+            split.ticker, split.execution_date, split.split_to, split.split_from
+          )
+          .into(),
+        ))
+      }
+      Ok(factor * &split.split_to / &split.split_from)
+    })
+}
+
+
+/// Scale the given aggregates of a ticker according to the provided
+/// splits.
+// We report errors as `Error`, like the remainder of the crate, despite
+// its size.
+#[allow(clippy::result_large_err)]
+fn scale<P, V>(
+  ticker: &str,
+  aggregates: Vec<Aggregate>,
+  splits: &[Split],
+  price: P,
+  volume: V,
+) -> Result<Vec<Aggregate>, Error>
+where
+  P: Fn(Num, &Num) -> Num,
+  V: Fn(f64, f64) -> f64,
+{
+  aggregates
+    .into_iter()
+    .map(|mut aggregate| {
+      if let Some(symbol) = &aggregate.symbol {
+        if symbol != ticker {
+          return Err(Error::Str(
+            format!("aggregate of {} cannot be scaled for splits of {}", symbol, ticker).into(),
+          ))
+        }
+      }
+
+      let factor = share_factor(ticker, trading_date(&aggregate.timestamp), splits)?;
+      let volume_factor = factor.to_f64().ok_or_else(|| {
+        Error::Str(format!("split factor {} is not representable as f64", factor).into())
+      })?;
+
+      aggregate.open_price = price(aggregate.open_price, &factor);
+      aggregate.close_price = price(aggregate.close_price, &factor);
+      aggregate.high_price = price(aggregate.high_price, &factor);
+      aggregate.low_price = price(aggregate.low_price, &factor);
+      aggregate.volume = volume(aggregate.volume, volume_factor);
+      Ok(aggregate)
+    })
+    .collect()
+}
+
+
+/// Adjust unadjusted aggregates of the given ticker for the provided
+/// splits.
+///
+/// The aggregates are expected to have been retrieved with adjustment
+/// for splits disabled. All splits of the ticker that happened after
+/// an aggregate's trading date are factored into its prices and
+/// volume, making them comparable to the most recent data. Splits of
+/// other tickers are ignored. An error is reported if an aggregate
+/// belongs to a different ticker or if a relevant split has a zero
+/// (or negative) ratio.
+#[allow(clippy::result_large_err)]
+pub fn adjust(
+  ticker: &str,
+  aggregates: Vec<Aggregate>,
+  splits: &[Split],
+) -> Result<Vec<Aggregate>, Error> {
+  scale(
+    ticker,
+    aggregates,
+    splits,
+    |price, factor| price / factor,
+    |volume, factor| volume * factor,
+  )
+}
+
+
+/// Revert the adjustment of aggregates of the given ticker for the
+/// provided splits.
+///
+/// This function is the inverse of `adjust`.
+#[allow(clippy::result_large_err)]
+pub fn unadjust(
+  ticker: &str,
+  aggregates: Vec<Aggregate>,
+  splits: &[Split],
+) -> Result<Vec<Aggregate>, Error> {
+  scale(
+    ticker,
+    aggregates,
+    splits,
+    |price, factor| price * factor,
+    |volume, factor| volume / factor,
+  )
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Create a split of the given ticker.
+  fn split_of(ticker: &str, date: &str, from: i64, to: i64) -> Split {
+    Split {
+      id: None,
+      ticker: ticker.into(),
+      execution_date: NaiveDate::from_str(date).unwrap(),
+      split_from: Num::from(from),
+      split_to: Num::from(to),
+    }
+  }
+
+  /// Create a split of AAPL stock.
+  fn split(date: &str, from: i64, to: i64) -> Split {
+    split_of("AAPL", date, from, to)
+  }
+
+  /// Create a daily aggregate for the given date with all prices set
+  /// to the same value.
+  fn aggregate(timestamp: &str, price: i64, volume: f64) -> Aggregate {
+    Aggregate {
+      symbol: None,
+      timestamp: DateTime::parse_from_rfc3339(timestamp).unwrap().into(),
+      volume,
+      open_price: Num::from(price),
+      close_price: Num::from(price),
+      high_price: Num::from(price),
+      low_price: Num::from(price),
+    }
+  }
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = SplitReq {
+      ticker: Some("AAPL".into()),
+      execution_date: vec![Filter::GreaterThan(
+        NaiveDate::from_str("2000-01-01").unwrap(),
+      )],
+      reverse_split: Some(false),
+      order: Some(Order::Descending),
+      sort: Some(Sort::ExecutionDate),
+      limit: Some(10),
+      cursor: None,
+    };
+
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "ticker=AAPL&execution_date.gt=2000-01-01&reverse_split=false&order=desc&sort=execution_date&limit=10"
+    );
+  }
+
+  /// Make sure that we can deserialize a page of splits.
+  #[test]
+  fn deserialize_splits() {
+    let response = r#"{
+  "request_id": "6a7e466379af0a71039d60cc78e72282",
+  "results": [
+    {
+      "execution_date": "2020-08-31",
+      "id": "E36416cce743c3964c5da63e1ef1626c0aece30fb47302eea5a49c0055c04e8d0",
+      "split_from": 1,
+      "split_to": 4,
+      "ticker": "AAPL"
+    },
+    {
+      "execution_date": "2005-02-28",
+      "split_from": 1,
+      "split_to": 2,
+      "ticker": "AAPL"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Split>>(response).unwrap();
+    assert_eq!(page.cursor, None);
+    assert_eq!(page.results.len(), 2);
+
+    let split = &page.results[0];
+    assert_eq!(split.ticker, "AAPL");
+    assert_eq!(split.execution_date, NaiveDate::from_str("2020-08-31").unwrap());
+    assert_eq!(split.split_from, Num::from(1));
+    assert_eq!(split.split_to, Num::from(4));
+    assert_eq!(page.results[1].id, None);
+  }
+
+  /// Check that trading dates are determined correctly around the
+  /// boundaries of the trading day.
+  #[test]
+  fn trading_dates() {
+    let date = |timestamp| {
+      let timestamp = DateTime::parse_from_rfc3339(timestamp).unwrap();
+      trading_date(&timestamp.into())
+    };
+    let expected = NaiveDate::from_str("2021-12-06").unwrap();
+
+    // Daily aggregates, with and without daylight saving time in
+    // effect.
+    assert_eq!(date("2021-12-06T00:00:00-05:00"), expected);
+    assert_eq!(
+      date("2021-11-01T00:00:00-04:00"),
+      NaiveDate::from_str("2021-11-01").unwrap()
+    );
+    // The first pre-market and the last after-hours aggregates.
+    assert_eq!(date("2021-12-06T04:00:00-05:00"), expected);
+    assert_eq!(date("2021-12-06T19:59:00-05:00"), expected);
+  }
+
+  /// Check that we can adjust aggregates for splits and revert the
+  /// adjustment.
+  #[test]
+  fn adjust_aggregates() {
+    let splits = vec![split("2020-08-31", 1, 4), split("2014-06-09", 1, 7)];
+    let aggregates = vec![
+      aggregate("2014-06-06T00:00:00-04:00", 560, 100.0),
+      aggregate("2014-06-09T00:00:00-04:00", 80, 700.0),
+      aggregate("2020-08-28T00:00:00-04:00", 500, 1000.0),
+      aggregate("2020-08-31T00:00:00-04:00", 125, 4000.0),
+    ];
+
+    let adjusted = adjust("AAPL", aggregates.clone(), &splits).unwrap();
+    assert_eq!(adjusted[0].open_price, Num::from(20));
+    assert_eq!(adjusted[0].close_price, Num::from(20));
+    assert_eq!(adjusted[0].high_price, Num::from(20));
+    assert_eq!(adjusted[0].low_price, Num::from(20));
+    assert!((adjusted[0].volume - 2800.0).abs() <= f64::EPSILON);
+    assert_eq!(adjusted[1].open_price, Num::from(20));
+    assert!((adjusted[1].volume - 2800.0).abs() <= f64::EPSILON);
+    assert_eq!(adjusted[2].open_price, Num::new(125, 1));
+    assert!((adjusted[2].volume - 4000.0).abs() <= f64::EPSILON);
+    assert_eq!(adjusted[3], aggregates[3]);
+
+    assert_eq!(unadjust("AAPL", adjusted, &splits).unwrap(), aggregates);
+  }
+
+  /// Check that splits of other tickers are not taken into account.
+  #[test]
+  fn adjust_ignores_other_tickers() {
+    let splits = vec![split("2020-08-31", 1, 4), split_of("TSLA", "2020-08-31", 1, 5)];
+    let aggregates = vec![aggregate("2020-08-28T00:00:00-04:00", 500, 1000.0)];
+
+    let adjusted = adjust("AAPL", aggregates, &splits).unwrap();
+    assert_eq!(adjusted[0].open_price, Num::from(125));
+    assert!((adjusted[0].volume - 4000.0).abs() <= f64::EPSILON);
+  }
+
+  /// Check that we refuse to adjust aggregates of a different ticker.
+  #[test]
+  fn adjust_rejects_other_ticker_aggregates() {
+    let splits = vec![split("2020-08-31", 1, 4)];
+    let aggregates = vec![Aggregate {
+      symbol: Some("TSLA".into()),
+      ..aggregate("2020-08-28T00:00:00-04:00", 500, 1000.0)
+    }];
+
+    let err = adjust("AAPL", aggregates, &splits).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "aggregate of TSLA cannot be scaled for splits of AAPL"
+    );
+  }
+
+  /// Check that splits with a zero ratio are reported as errors
+  /// instead of causing a division by zero.
+  #[test]
+  fn adjust_rejects_zero_ratio() {
+    let aggregates = vec![aggregate("2020-08-28T00:00:00-04:00", 500, 1000.0)];
+
+    let splits = vec![split("2020-08-31", 0, 4)];
+    let err = adjust("AAPL", aggregates.clone(), &splits).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "split of AAPL on 2020-08-31 has invalid ratio 4:0"
+    );
+
+    let splits = vec![split("2020-08-31", 1, 0)];
+    let err = unadjust("AAPL", aggregates.clone(), &splits).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "split of AAPL on 2020-08-31 has invalid ratio 0:1"
+    );
+
+    // Invalid splits not relevant to the aggregates are ignored.
+    let splits = vec![split("2020-08-01", 0, 4), split_of("TSLA", "2020-08-31", 0, 5)];
+    assert_eq!(adjust("AAPL", aggregates.clone(), &splits).unwrap(), aggregates);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_splits() {
+    let client = Client::from_env().unwrap();
+    let request = SplitReq {
+      ticker: Some("AAPL".into()),
+      ..Default::default()
+    };
+
+    let splits = client
+      .pages::<List>(request)
+      .try_concat()
+      .await
+      .unwrap();
+
+    let split = splits
+      .iter()
+      .find(|split| split.execution_date == NaiveDate::from_str("2020-08-31").unwrap())
+      .unwrap();
+    assert_eq!(split.split_from, Num::from(1));
+    assert_eq!(split.split_to, Num::from(4));
+  }
+}
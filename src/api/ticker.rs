@@ -1,24 +1,38 @@
 // Copyright (C) 2020-2022 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use chrono::NaiveDate;
+
 use serde::Deserialize;
 
+use url::form_urlencoded::Serializer;
+
 use crate::api::response::Response;
+use crate::api::tickers;
+use crate::endpoint::finish_query;
 use crate::Str;
 
 
 /// An enum describing the ticker's market.
+// Note that the v3 reference endpoints report markets in lower case
+// and use slightly different names, hence the aliases.
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
 pub enum Market {
   /// The stock market.
-  #[serde(rename = "STOCKS")]
+  #[serde(rename = "STOCKS", alias = "stocks")]
   Stocks,
   /// The indices market.
-  #[serde(rename = "INDEX")]
+  #[serde(rename = "INDEX", alias = "indices")]
   Indices,
   /// The foreign exchange market.
-  #[serde(rename = "FX")]
+  #[serde(rename = "FX", alias = "fx")]
   ForeignExchange,
+  /// The crypto currency market.
+  #[serde(rename = "CRYPTO", alias = "crypto")]
+  Crypto,
+  /// The over-the-counter market.
+  #[serde(rename = "OTC", alias = "otc")]
+  Otc,
 }
 
 impl AsRef<str> for Market {
@@ -27,6 +41,8 @@ impl AsRef<str> for Market {
       Market::Stocks => "STOCKS",
       Market::Indices => "INDEX",
       Market::ForeignExchange => "FX",
+      Market::Crypto => "CRYPTO",
+      Market::Otc => "OTC",
     }
   }
 }
@@ -91,6 +107,112 @@ pub struct TickerResp {
 }
 
 
+/// A GET request to be made to the `/v3/reference/tickers/<ticker>`
+/// endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetailsReq {
+  /// The ticker to retrieve details for.
+  pub ticker: String,
+  /// The date to retrieve the details as of.
+  ///
+  /// If not set, the most recent details are reported.
+  pub date: Option<NaiveDate>,
+}
+
+
+/// The address of a company's headquarters.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Address {
+  /// The first line of the address.
+  #[serde(rename = "address1")]
+  pub address1: Option<String>,
+  /// The second line of the address, if any.
+  #[serde(rename = "address2")]
+  pub address2: Option<String>,
+  /// The city.
+  #[serde(rename = "city")]
+  pub city: Option<String>,
+  /// The state.
+  #[serde(rename = "state")]
+  pub state: Option<String>,
+  /// The postal code.
+  #[serde(rename = "postal_code")]
+  pub postal_code: Option<String>,
+}
+
+
+/// URLs to a company's branding assets.
+///
+/// Please note that accessing these URLs requires the API key to be
+/// supplied.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Branding {
+  /// The URL of the company's logo.
+  #[serde(rename = "logo_url")]
+  pub logo_url: Option<String>,
+  /// The URL of the company's icon.
+  #[serde(rename = "icon_url")]
+  pub icon_url: Option<String>,
+}
+
+
+/// Ticker details as returned by the `/v3/reference/tickers/<ticker>`
+/// endpoint.
+///
+/// Please note that not all fields available in a request are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Details {
+  /// The basic ticker information.
+  #[serde(flatten)]
+  pub ticker: tickers::Ticker,
+  /// The MIC of the ticker's primary exchange.
+  #[serde(rename = "primary_exchange")]
+  pub primary_exchange: Option<String>,
+  /// The company's CIK.
+  #[serde(rename = "cik")]
+  pub cik: Option<String>,
+  /// The company's market capitalization.
+  #[serde(rename = "market_cap")]
+  pub market_cap: Option<f64>,
+  /// The number of outstanding shares of this share class.
+  #[serde(rename = "share_class_shares_outstanding")]
+  pub share_class_shares_outstanding: Option<u64>,
+  /// The number of outstanding shares, weighted over all share
+  /// classes.
+  #[serde(rename = "weighted_shares_outstanding")]
+  pub weighted_shares_outstanding: Option<u64>,
+  /// The company's standard industrial classification (SIC) code.
+  #[serde(rename = "sic_code")]
+  pub sic_code: Option<String>,
+  /// The description of the SIC code.
+  #[serde(rename = "sic_description")]
+  pub sic_description: Option<String>,
+  /// The date the ticker was first listed.
+  #[serde(rename = "list_date")]
+  pub list_date: Option<NaiveDate>,
+  /// The address of the company's headquarters.
+  #[serde(rename = "address")]
+  pub address: Option<Address>,
+  /// The URL of the company's website.
+  #[serde(rename = "homepage_url")]
+  pub homepage_url: Option<String>,
+  /// URLs to the company's branding assets.
+  #[serde(rename = "branding")]
+  pub branding: Option<Branding>,
+}
+
+
+/// A company related to a ticker as returned by the
+/// `/v1/related-companies/<ticker>` endpoint.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Related {
+  /// The ticker symbol of the related company.
+  #[serde(rename = "ticker")]
+  pub ticker: String,
+}
+
+
 Endpoint! {
   /// The representation of a GET request to the
   /// `/v2/reference/tickers/<ticker>` endpoint.
@@ -113,17 +235,191 @@ Endpoint! {
 }
 
 
-#[cfg(not(target_arch = "wasm32"))]
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/reference/tickers/<ticker>` endpoint.
+  pub GetDetails(DetailsReq),
+  Ok => Response<Details>, [
+    /// The ticker details were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetDetailsError, [
+    /// The ticker was not found, possibly as of the given date.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v3/reference/tickers/{}", input.ticker).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(date) = &input.date {
+      query.append_pair("date", &date.format("%Y-%m-%d").to_string());
+    }
+    Ok(finish_query(query))
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v1/related-companies/<ticker>` endpoint.
+  pub GetRelated(String),
+  Ok => Response<Vec<Related>>, [
+    /// The related companies were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetRelatedError, [
+    /// The ticker was not found.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v1/related-companies/{}", input).into()
+  }
+}
+
+
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
   use test_log::test;
 
+  #[cfg(not(target_arch = "wasm32"))]
   use crate::Client;
+  #[cfg(not(target_arch = "wasm32"))]
   use crate::RequestError;
 
 
+  /// Check that we emit the expected query for ticker details.
+  #[test]
+  fn encode_details_query() {
+    let request = DetailsReq {
+      ticker: "AAPL".into(),
+      date: None,
+    };
+    assert_eq!(GetDetails::path(&request), "/v3/reference/tickers/AAPL");
+    assert_eq!(GetDetails::query(&request).unwrap(), None);
+
+    let request = DetailsReq {
+      date: Some(NaiveDate::from_str("2019-01-02").unwrap()),
+      ..request
+    };
+    assert_eq!(
+      GetDetails::query(&request).unwrap().unwrap(),
+      "date=2019-01-02"
+    );
+  }
+
+  /// Make sure that we can deserialize ticker details.
+  #[test]
+  fn deserialize_details() {
+    let response = r#"{
+  "request_id": "31d59dda-80e5-4721-8496-d0d32a654afe",
+  "results": {
+    "active": true,
+    "address": {
+      "address1": "One Apple Park Way",
+      "city": "Cupertino",
+      "postal_code": "95014",
+      "state": "CA"
+    },
+    "branding": {
+      "icon_url": "https://api.polygon.io/v1/reference/company-branding/d3d3LmFwcGxlLmNvbQ/images/2022-01-10_icon.png",
+      "logo_url": "https://api.polygon.io/v1/reference/company-branding/d3d3LmFwcGxlLmNvbQ/images/2022-01-10_logo.svg"
+    },
+    "cik": "0000320193",
+    "composite_figi": "BBG000B9XRY4",
+    "currency_name": "usd",
+    "description": "Apple designs a wide variety of consumer electronic devices.",
+    "homepage_url": "https://www.apple.com",
+    "list_date": "1980-12-12",
+    "locale": "us",
+    "market": "stocks",
+    "market_cap": 2771126040150,
+    "name": "Apple Inc.",
+    "phone_number": "(408) 996-1010",
+    "primary_exchange": "XNAS",
+    "share_class_figi": "BBG001S5N8V8",
+    "share_class_shares_outstanding": 16406400000,
+    "sic_code": "3571",
+    "sic_description": "ELECTRONIC COMPUTERS",
+    "ticker": "AAPL",
+    "ticker_root": "AAPL",
+    "total_employees": 154000,
+    "type": "CS",
+    "weighted_shares_outstanding": 16334371000
+  },
+  "status": "OK"
+}"#;
+
+    let details = from_json::<Response<Details>>(response)
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    assert_eq!(details.ticker.ticker, "AAPL");
+    assert_eq!(details.ticker.name, "Apple Inc.");
+    assert_eq!(details.ticker.market, Market::Stocks);
+    assert_eq!(details.ticker.locale, "US");
+    assert_eq!(details.ticker.currency.as_deref(), Some("USD"));
+    assert_eq!(details.ticker.type_, Some(Type::CommonStock));
+    assert_eq!(details.primary_exchange.as_deref(), Some("XNAS"));
+    assert_eq!(details.cik.as_deref(), Some("0000320193"));
+    assert_eq!(details.market_cap, Some(2_771_126_040_150f64));
+    assert_eq!(details.share_class_shares_outstanding, Some(16_406_400_000));
+    assert_eq!(details.weighted_shares_outstanding, Some(16_334_371_000));
+    assert_eq!(details.sic_code.as_deref(), Some("3571"));
+    assert_eq!(
+      details.list_date,
+      Some(NaiveDate::from_str("1980-12-12").unwrap())
+    );
+
+    let address = details.address.unwrap();
+    assert_eq!(address.address1.as_deref(), Some("One Apple Park Way"));
+    assert_eq!(address.address2, None);
+    assert_eq!(address.city.as_deref(), Some("Cupertino"));
+
+    assert_eq!(details.homepage_url.as_deref(), Some("https://www.apple.com"));
+    assert!(details.branding.unwrap().logo_url.unwrap().ends_with("logo.svg"));
+  }
+
+  /// Make sure that we can deserialize related companies.
+  #[test]
+  fn deserialize_related() {
+    let response = r#"{
+  "request_id": "31d59dda-80e5-4721-8496-d0d32a654afe",
+  "results": [
+    {"ticker": "MSFT"},
+    {"ticker": "GOOGL"},
+    {"ticker": "AMZN"}
+  ],
+  "status": "OK",
+  "stock_symbol": "AAPL"
+}"#;
+
+    let related = from_json::<Response<Vec<Related>>>(response)
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    let tickers = related
+      .iter()
+      .map(|related| related.ticker.as_str())
+      .collect::<Vec<_>>();
+    assert_eq!(tickers, vec!["MSFT", "GOOGL", "AMZN"]);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
   #[test(tokio::test)]
   async fn request_aapl_ticker() {
     let client = Client::from_env().unwrap();
@@ -142,4 +438,43 @@ mod tests {
       Err(..) => panic!("unexpected error: {:?}", result),
     }
   }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_details() {
+    let client = Client::from_env().unwrap();
+    let request = DetailsReq {
+      ticker: "AAPL".into(),
+      date: Some(NaiveDate::from_str("2021-11-01").unwrap()),
+    };
+    let details = client
+      .issue::<GetDetails>(request)
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    assert_eq!(details.ticker.ticker, "AAPL");
+    assert_eq!(details.ticker.market, Market::Stocks);
+    assert_eq!(details.primary_exchange.as_deref(), Some("XNAS"));
+    assert_eq!(
+      details.list_date,
+      Some(NaiveDate::from_str("1980-12-12").unwrap())
+    );
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_related() {
+    let client = Client::from_env().unwrap();
+    let related = client
+      .issue::<GetRelated>("AAPL".into())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    assert!(!related.is_empty());
+    assert!(related.iter().all(|related| related.ticker != "AAPL"));
+  }
 }
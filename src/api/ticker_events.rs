@@ -0,0 +1,273 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::Duration;
+use chrono::NaiveDate;
+
+use serde::Deserialize;
+
+use crate::api::response::Response;
+use crate::Str;
+
+
+/// The type of a ticker event.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum Type {
+  /// The ticker symbol changed.
+  #[serde(rename = "ticker_change")]
+  TickerChange,
+  /// Any other type.
+  #[serde(other)]
+  Other,
+}
+
+
+/// The details of a ticker symbol change.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct TickerChange {
+  /// The ticker symbol in effect from the event's date on.
+  #[serde(rename = "ticker")]
+  pub ticker: String,
+}
+
+
+/// An event in the history of a ticker.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Event {
+  /// The date the event took effect.
+  #[serde(rename = "date")]
+  pub date: NaiveDate,
+  /// The type of the event.
+  #[serde(rename = "type")]
+  pub type_: Type,
+  /// The details of a symbol change.
+  ///
+  /// This field is set for events of type `TickerChange`.
+  #[serde(rename = "ticker_change")]
+  pub ticker_change: Option<TickerChange>,
+}
+
+
+/// The events of a ticker as returned by the
+/// `/vX/reference/tickers/<id>/events` endpoint.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Events {
+  /// The name of the company.
+  #[serde(rename = "name")]
+  pub name: String,
+  /// The company's CIK.
+  #[serde(rename = "cik")]
+  pub cik: Option<String>,
+  /// The composite FIGI of the ticker.
+  #[serde(rename = "composite_figi")]
+  pub composite_figi: Option<String>,
+  /// The events, most recent first.
+  #[serde(rename = "events", default)]
+  pub events: Vec<Event>,
+}
+
+
+/// A date range during which a company traded under a certain symbol.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SymbolRange {
+  /// The ticker symbol.
+  pub symbol: String,
+  /// The first date of the range.
+  pub start: NaiveDate,
+  /// The last date of the range (inclusive).
+  pub end: NaiveDate,
+}
+
+impl Events {
+  /// Resolve the symbols a company traded under during the given
+  /// date range (inclusive).
+  ///
+  /// The returned ranges are ordered by date and cover the requested
+  /// range without gaps, which makes them suitable for requesting
+  /// aggregates via `aggregates::Get` across symbol changes. Dates
+  /// before the first known symbol change are attributed to the
+  /// symbol it introduced. No ranges are reported if the ticker has
+  /// no known symbol.
+  pub fn symbols(&self, start: NaiveDate, end: NaiveDate) -> Vec<SymbolRange> {
+    let mut changes = self
+      .events
+      .iter()
+      .filter_map(|event| {
+        event
+          .ticker_change
+          .as_ref()
+          .map(|change| (event.date, change.ticker.as_str()))
+      })
+      .collect::<Vec<_>>();
+    changes.sort_by_key(|(date, _)| *date);
+
+    let mut ranges = Vec::<SymbolRange>::new();
+    for (i, (date, symbol)) in changes.iter().enumerate() {
+      let range_start = if i == 0 { start } else { (*date).max(start) };
+      let range_end = match changes.get(i + 1) {
+        Some((next, _)) => (*next - Duration::days(1)).min(end),
+        None => end,
+      };
+
+      if range_start > range_end {
+        continue
+      }
+
+      match ranges.last_mut() {
+        // Merge consecutive ranges of the same symbol.
+        Some(last) if last.symbol == *symbol => last.end = range_end,
+        _ => ranges.push(SymbolRange {
+          symbol: symbol.to_string(),
+          start: range_start,
+          end: range_end,
+        }),
+      }
+    }
+    ranges
+  }
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/vX/reference/tickers/<id>/events` endpoint.
+  ///
+  /// The input identifies the ticker and may be a ticker symbol, a
+  /// CIK, or a composite FIGI.
+  pub Get(String),
+  Ok => Response<Events>, [
+    /// The ticker events were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The ticker was not found.
+    /* 404 */ NOT_FOUND => NotFound,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/vX/reference/tickers/{}/events", input).into()
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  const EVENTS: &str = r#"{
+  "request_id": "31d59dda-80e5-4721-8496-d0d32a654afe",
+  "results": {
+    "cik": "0001326801",
+    "composite_figi": "BBG000MM2P62",
+    "events": [
+      {"date": "2022-06-09", "ticker_change": {"ticker": "META"}, "type": "ticker_change"},
+      {"date": "2012-05-18", "ticker_change": {"ticker": "FB"}, "type": "ticker_change"}
+    ],
+    "name": "Meta Platforms, Inc. Class A Common Stock"
+  },
+  "status": "OK"
+}"#;
+
+
+  fn date(date: &str) -> NaiveDate {
+    NaiveDate::from_str(date).unwrap()
+  }
+
+  fn range(symbol: &str, start: &str, end: &str) -> SymbolRange {
+    SymbolRange {
+      symbol: symbol.into(),
+      start: date(start),
+      end: date(end),
+    }
+  }
+
+
+  /// Make sure that we can deserialize ticker events.
+  #[test]
+  fn deserialize_events() {
+    let events = from_json::<Response<Events>>(EVENTS)
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    assert_eq!(events.name, "Meta Platforms, Inc. Class A Common Stock");
+    assert_eq!(events.cik.as_deref(), Some("0001326801"));
+    assert_eq!(events.events.len(), 2);
+    assert_eq!(events.events[0].date, date("2022-06-09"));
+    assert_eq!(events.events[0].type_, Type::TickerChange);
+    assert_eq!(
+      events.events[0].ticker_change.as_ref().unwrap().ticker,
+      "META"
+    );
+  }
+
+  /// Check that we resolve the symbols of a ticker correctly.
+  #[test]
+  fn resolve_symbols() {
+    let events = from_json::<Response<Events>>(EVENTS)
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    let symbols = events.symbols(date("2022-01-01"), date("2022-12-31"));
+    assert_eq!(
+      symbols,
+      vec![
+        range("FB", "2022-01-01", "2022-06-08"),
+        range("META", "2022-06-09", "2022-12-31"),
+      ]
+    );
+
+    let symbols = events.symbols(date("2021-01-01"), date("2021-12-31"));
+    assert_eq!(symbols, vec![range("FB", "2021-01-01", "2021-12-31")]);
+
+    let symbols = events.symbols(date("2023-01-01"), date("2023-01-31"));
+    assert_eq!(symbols, vec![range("META", "2023-01-01", "2023-01-31")]);
+
+    // Dates before the first event are attributed to the first symbol.
+    let symbols = events.symbols(date("2010-01-01"), date("2012-12-31"));
+    assert_eq!(symbols, vec![range("FB", "2010-01-01", "2012-12-31")]);
+
+    let events = Events {
+      events: Vec::new(),
+      ..events
+    };
+    let symbols = events.symbols(date("2022-01-01"), date("2022-12-31"));
+    assert_eq!(symbols, Vec::new());
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_meta_events() {
+    let client = Client::from_env().unwrap();
+    let events = client
+      .issue::<Get>("META".into())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    let symbols = events.symbols(date("2022-06-01"), date("2022-06-30"));
+    assert_eq!(
+      symbols,
+      vec![
+        range("FB", "2022-06-01", "2022-06-08"),
+        range("META", "2022-06-09", "2022-06-30"),
+      ]
+    );
+  }
+}
@@ -0,0 +1,372 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::NaiveDate;
+
+use serde::de::Deserializer;
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::api::ticker::Market;
+use crate::api::ticker::Type;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// Convert a `Market` into the representation used by the v3
+/// reference endpoints for filtering.
+fn market_filter(market: Market) -> &'static str {
+  match market {
+    Market::Stocks => "stocks",
+    Market::Indices => "indices",
+    Market::ForeignExchange => "fx",
+    Market::Crypto => "crypto",
+    Market::Otc => "otc",
+  }
+}
+
+
+/// Deserialize a string, converting it to upper case.
+fn uppercase_from_str<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let string = String::deserialize(deserializer)?;
+  Ok(string.to_uppercase())
+}
+
+
+/// Deserialize an optional string, converting it to upper case.
+fn optional_uppercase_from_str<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let string = Option::<String>::deserialize(deserializer)?;
+  Ok(string.map(|string| string.to_uppercase()))
+}
+
+
+/// A ticker as returned by the `/v3/reference/tickers` endpoint.
+///
+/// The v3 reference endpoints report locale and currency in lower
+/// case. They are converted to upper case, to be consistent with
+/// `ticker::Ticker`.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Ticker {
+  /// The ticker.
+  #[serde(rename = "ticker")]
+  pub ticker: String,
+  /// The ticker's name.
+  #[serde(rename = "name")]
+  pub name: String,
+  /// The ticker's market.
+  #[serde(rename = "market")]
+  pub market: Market,
+  /// The locale, e.g., `US`.
+  #[serde(rename = "locale", deserialize_with = "uppercase_from_str")]
+  pub locale: String,
+  /// The ticker's currency, e.g., `USD`.
+  ///
+  /// This field is not set for tickers not traded in a currency, e.g.,
+  /// certain indices.
+  #[serde(
+    rename = "currency_name",
+    default,
+    deserialize_with = "optional_uppercase_from_str"
+  )]
+  pub currency: Option<String>,
+  /// Whether the ticker is still active.
+  #[serde(rename = "active")]
+  pub active: bool,
+  /// The ticker's type.
+  #[serde(rename = "type")]
+  pub type_: Option<Type>,
+}
+
+
+/// The field to sort tickers by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the ticker symbol.
+  Ticker,
+  /// Sort by the ticker's name.
+  Name,
+  /// Sort by the ticker's market.
+  Market,
+  /// Sort by the ticker's locale.
+  Locale,
+  /// Sort by the ticker's primary exchange.
+  PrimaryExchange,
+  /// Sort by the ticker's type.
+  Type,
+  /// Sort by the ticker's CIK.
+  Cik,
+  /// Sort by the time the ticker was last updated.
+  LastUpdated,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Ticker => "ticker",
+      Sort::Name => "name",
+      Sort::Market => "market",
+      Sort::Locale => "locale",
+      Sort::PrimaryExchange => "primary_exchange",
+      Sort::Type => "type",
+      Sort::Cik => "cik",
+      Sort::LastUpdated => "last_updated_utc",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v3/reference/tickers` endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TickerReq {
+  /// Only report tickers of this type, e.g., `CS` for common stock.
+  ///
+  /// The available types can be retrieved via the `ticker_types`
+  /// endpoint.
+  pub type_: Option<String>,
+  /// Only report tickers of this market.
+  pub market: Option<Market>,
+  /// Only report tickers with this primary exchange, identified by
+  /// its MIC, e.g., `XNAS`.
+  pub exchange: Option<String>,
+  /// Only report the ticker with this CUSIP.
+  pub cusip: Option<String>,
+  /// Only report the ticker with this CIK.
+  pub cik: Option<String>,
+  /// Report tickers as they were available on this date.
+  pub date: Option<NaiveDate>,
+  /// Only report active (`true`) or delisted (`false`) tickers.
+  pub active: Option<bool>,
+  /// Only report tickers whose symbol or name contains this text.
+  pub search: Option<String>,
+  /// The order in which to report tickers.
+  pub order: Option<Order>,
+  /// The field to sort tickers by.
+  pub sort: Option<Sort>,
+  /// The maximum number of tickers to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the
+  /// `/v3/reference/tickers` endpoint.
+  pub List(TickerReq),
+  Ok => Page<Ticker>, [
+    /// The tickers were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => ListError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(_input: &Self::Input) -> Str {
+    "/v3/reference/tickers".into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      if let Some(type_) = &input.type_ {
+        query.append_pair("type", type_);
+      }
+      if let Some(market) = input.market {
+        query.append_pair("market", market_filter(market));
+      }
+      if let Some(exchange) = &input.exchange {
+        query.append_pair("exchange", exchange);
+      }
+      if let Some(cusip) = &input.cusip {
+        query.append_pair("cusip", cusip);
+      }
+      if let Some(cik) = &input.cik {
+        query.append_pair("cik", cik);
+      }
+      if let Some(date) = &input.date {
+        query.append_pair("date", &date.format("%Y-%m-%d").to_string());
+      }
+      if let Some(active) = &input.active {
+        query.append_pair("active", &active.to_string());
+      }
+      if let Some(search) = &input.search {
+        query.append_pair("search", search);
+      }
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for List {
+  type Item = Ticker;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::str::FromStr as _;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::StreamExt as _;
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = TickerReq {
+      type_: Some("CS".into()),
+      market: Some(Market::Stocks),
+      exchange: Some("XNAS".into()),
+      date: Some(NaiveDate::from_str("2021-11-01").unwrap()),
+      active: Some(true),
+      search: Some("apple inc".into()),
+      order: Some(Order::Descending),
+      sort: Some(Sort::Ticker),
+      limit: Some(100),
+      ..Default::default()
+    };
+
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "type=CS&market=stocks&exchange=XNAS&date=2021-11-01&active=true&search=apple+inc&order=desc&sort=ticker&limit=100"
+    );
+
+    let request = TickerReq {
+      cursor: Some("YWN0aXZlPXRydWU".into()),
+      ..request
+    };
+    let query = List::query(&request).unwrap().unwrap();
+    assert_eq!(query, "cursor=YWN0aXZlPXRydWU");
+
+    assert_eq!(List::query(&TickerReq::default()).unwrap(), None);
+  }
+
+  /// Make sure that we can deserialize a page of tickers.
+  #[test]
+  fn deserialize_tickers() {
+    let response = r#"{
+  "count": 2,
+  "next_url": "https://api.polygon.io/v3/reference/tickers?cursor=YWN0aXZlPXRydWUmZGF0ZT0yMDIxLTA0LTI1",
+  "request_id": "e70013d92930de90e089dc8fa098888e",
+  "results": [
+    {
+      "active": true,
+      "cik": "0001090872",
+      "composite_figi": "BBG000BWQYZ5",
+      "currency_name": "usd",
+      "last_updated_utc": "2021-04-25T00:00:00Z",
+      "locale": "us",
+      "market": "stocks",
+      "name": "Agilent Technologies Inc.",
+      "primary_exchange": "XNYS",
+      "share_class_figi": "BBG001SCTQY4",
+      "ticker": "A",
+      "type": "CS"
+    },
+    {
+      "active": true,
+      "last_updated_utc": "2022-01-03T00:00:00Z",
+      "locale": "us",
+      "market": "indices",
+      "name": "S&P 500",
+      "ticker": "I:SPX",
+      "type": "INDEX"
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Ticker>>(response).unwrap();
+    assert!(page.cursor.is_some());
+    assert_eq!(page.results.len(), 2);
+
+    let ticker = &page.results[0];
+    assert_eq!(ticker.ticker, "A");
+    assert_eq!(ticker.name, "Agilent Technologies Inc.");
+    assert_eq!(ticker.market, Market::Stocks);
+    assert_eq!(ticker.locale, "US");
+    assert_eq!(ticker.currency.as_deref(), Some("USD"));
+    assert!(ticker.active);
+    assert_eq!(ticker.type_, Some(Type::CommonStock));
+
+    let ticker = &page.results[1];
+    assert_eq!(ticker.market, Market::Indices);
+    assert_eq!(ticker.currency, None);
+    assert_eq!(ticker.type_, Some(Type::Other));
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_tickers() {
+    let client = Client::from_env().unwrap();
+    let request = TickerReq {
+      type_: Some("CS".into()),
+      market: Some(Market::Stocks),
+      search: Some("Apple".into()),
+      active: Some(true),
+      limit: Some(100),
+      ..Default::default()
+    };
+
+    // Only retrieve the first two pages to not run into rate limits.
+    let tickers = client
+      .pages::<List>(request)
+      .take(2)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert!(tickers.len() <= 200);
+    assert!(tickers.iter().any(|ticker| ticker.ticker == "AAPL"));
+    assert!(tickers.iter().all(|ticker| ticker.market == Market::Stocks));
+    assert!(tickers.iter().all(|ticker| ticker.active));
+  }
+}
@@ -0,0 +1,319 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use chrono::serde::ts_nanoseconds::deserialize as datetime_from_nanos;
+use chrono::serde::ts_nanoseconds_option::deserialize as optional_datetime_from_nanos;
+use chrono::DateTime;
+use chrono::Utc;
+
+use num_decimal::Num;
+
+use serde::Deserialize;
+
+use url::form_urlencoded::Serializer;
+
+use crate::api::filter::append_filters;
+use crate::api::page::Order;
+use crate::api::page::Page;
+use crate::api::page::Paginated;
+use crate::api::Filter;
+use crate::endpoint::finish_query;
+use crate::Str;
+
+
+/// Convert a date time into a nanosecond timestamp, as used by
+/// Polygon for filtering.
+pub(crate) fn nanos(time: &DateTime<Utc>) -> i64 {
+  time.timestamp() * 1_000_000_000 + i64::from(time.timestamp_subsec_nanos())
+}
+
+
+/// Append the given timestamp filters to a query.
+pub(crate) fn append_timestamp_filters(
+  query: &mut Serializer<'_, String>,
+  filters: &[Filter<DateTime<Utc>>],
+) {
+  let filters = filters
+    .iter()
+    .map(|filter| filter.map(nanos))
+    .collect::<Vec<_>>();
+  append_filters(query, "timestamp", &filters);
+}
+
+
+/// The field to sort trades and quotes by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sort {
+  /// Sort by the SIP timestamp.
+  Timestamp,
+}
+
+impl AsRef<str> for Sort {
+  fn as_ref(&self) -> &'static str {
+    match *self {
+      Sort::Timestamp => "timestamp",
+    }
+  }
+}
+
+
+/// A GET request to be made to the `/v3/trades/<symbol>` endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TradeReq {
+  /// The ticker symbol to request trades for.
+  pub symbol: String,
+  /// Filters on the trades' SIP timestamp.
+  pub timestamp: Vec<Filter<DateTime<Utc>>>,
+  /// The order in which to report trades.
+  pub order: Option<Order>,
+  /// The field to sort trades by.
+  pub sort: Option<Sort>,
+  /// The maximum number of trades to report per page.
+  pub limit: Option<usize>,
+  /// The cursor referencing the page to retrieve.
+  ///
+  /// This field is set automatically when paginating via
+  /// `Client::pages` and should not have to be set manually. If set,
+  /// all other filters are ignored, as they are encoded in the cursor.
+  pub cursor: Option<String>,
+}
+
+
+/// A trade as returned by the `/v3/trades/<symbol>` endpoint.
+///
+/// Please note that not all fields available in a response are
+/// represented here.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Trade {
+  /// The conditions of the trade.
+  #[serde(rename = "conditions", default)]
+  pub conditions: Vec<u64>,
+  /// The trade correction indicator.
+  #[serde(rename = "correction")]
+  pub correction: Option<u64>,
+  /// The ID of the exchange the trade occurred on.
+  #[serde(rename = "exchange")]
+  pub exchange: u64,
+  /// The trade ID, unique per ticker, exchange, and day.
+  #[serde(rename = "id")]
+  pub id: String,
+  /// The time at which the trade was generated at the exchange.
+  #[serde(
+    rename = "participant_timestamp",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub participant_timestamp: Option<DateTime<Utc>>,
+  /// The price.
+  #[serde(rename = "price")]
+  pub price: Num,
+  /// The sequence number, unique per ticker and day.
+  ///
+  /// Sequence numbers increase but are not necessarily consecutive.
+  #[serde(rename = "sequence_number")]
+  pub sequence_number: u64,
+  /// The time at which the SIP received the trade.
+  #[serde(rename = "sip_timestamp", deserialize_with = "datetime_from_nanos")]
+  pub sip_timestamp: DateTime<Utc>,
+  /// The number of shares traded.
+  #[serde(rename = "size")]
+  pub size: u64,
+  /// The tape the trade was reported to.
+  ///
+  /// `1` is tape A (NYSE), `2` is tape B (NYSE Arca, NYSE American,
+  /// and regional exchanges), and `3` is tape C (Nasdaq).
+  #[serde(rename = "tape")]
+  pub tape: Option<u8>,
+  /// The ID of the trade reporting facility, if any.
+  #[serde(rename = "trf_id")]
+  pub trf_id: Option<u64>,
+  /// The time at which the trade reporting facility received the
+  /// trade.
+  #[serde(
+    rename = "trf_timestamp",
+    default,
+    deserialize_with = "optional_datetime_from_nanos"
+  )]
+  pub trf_timestamp: Option<DateTime<Utc>>,
+}
+
+
+Endpoint! {
+  /// The representation of a GET request to the `/v3/trades/<symbol>`
+  /// endpoint.
+  pub Get(TradeReq),
+  Ok => Page<Trade>, [
+    /// The trades were retrieved successfully.
+    /* 200 */ OK,
+  ],
+  Err => GetError, [
+    /// The request was invalid, e.g., because of an invalid filter.
+    /* 400 */ BAD_REQUEST => InvalidInput,
+  ]
+
+  fn path(input: &Self::Input) -> Str {
+    format!("/v3/trades/{}", input.symbol).into()
+  }
+
+  fn query(input: &Self::Input) -> Result<Option<Str>, Self::ConversionError> {
+    let mut query = Serializer::new(String::new());
+    if let Some(cursor) = &input.cursor {
+      query.append_pair("cursor", cursor);
+    } else {
+      append_timestamp_filters(&mut query, &input.timestamp);
+      if let Some(order) = &input.order {
+        query.append_pair("order", order.as_ref());
+      }
+      if let Some(sort) = &input.sort {
+        query.append_pair("sort", sort.as_ref());
+      }
+      if let Some(limit) = &input.limit {
+        query.append_pair("limit", &limit.to_string());
+      }
+    }
+    Ok(finish_query(query))
+  }
+}
+
+impl Paginated for Get {
+  type Item = Trade;
+
+  fn set_cursor(input: &mut Self::Input, cursor: String) {
+    input.cursor = Some(cursor);
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use http_endpoint::Endpoint as _;
+
+  use serde_json::from_str as from_json;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::StreamExt as _;
+  #[cfg(not(target_arch = "wasm32"))]
+  use futures::TryStreamExt as _;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use test_log::test;
+
+  #[cfg(not(target_arch = "wasm32"))]
+  use crate::Client;
+
+
+  /// Check that we emit the expected query parameters.
+  #[test]
+  fn encode_query() {
+    let request = TradeReq {
+      symbol: "AAPL".into(),
+      timestamp: vec![
+        Filter::GreaterThanOrEqual(
+          DateTime::parse_from_rfc3339("2021-11-01T09:30:00-04:00").unwrap().into(),
+        ),
+        Filter::LessThan(
+          DateTime::parse_from_rfc3339("2021-11-01T09:31:00-04:00").unwrap().into(),
+        ),
+      ],
+      order: Some(Order::Ascending),
+      sort: Some(Sort::Timestamp),
+      limit: Some(1000),
+      cursor: None,
+    };
+
+    let query = Get::query(&request).unwrap().unwrap();
+    assert_eq!(
+      query,
+      "timestamp.gte=1635773400000000000&timestamp.lt=1635773460000000000&order=asc&sort=timestamp&limit=1000"
+    );
+
+    let request = TradeReq {
+      cursor: Some("YXA9NTU1NDEyNg".into()),
+      ..request
+    };
+    let query = Get::query(&request).unwrap().unwrap();
+    assert_eq!(query, "cursor=YXA9NTU1NDEyNg");
+  }
+
+  /// Make sure that we can deserialize a page of trades.
+  #[test]
+  fn deserialize_trades() {
+    let response = r#"{
+  "next_url": "https://api.polygon.io/v3/trades/AAPL?cursor=YWN0aXZlPXRydWUmZGF0ZT0yMDIxLTA0LTI1",
+  "request_id": "a47d1beb8c11b6ae897ab76cdbbf35a3",
+  "results": [
+    {
+      "conditions": [12, 41],
+      "exchange": 11,
+      "id": "1",
+      "participant_timestamp": 1517562000015577000,
+      "price": 171.55,
+      "sequence_number": 1063,
+      "sip_timestamp": 1517562000016036581,
+      "size": 100,
+      "tape": 3
+    }
+  ],
+  "status": "OK"
+}"#;
+
+    let page = from_json::<Page<Trade>>(response).unwrap();
+    assert!(page.cursor.is_some());
+    assert_eq!(page.results.len(), 1);
+
+    let trade = &page.results[0];
+    assert_eq!(trade.conditions, vec![12, 41]);
+    assert_eq!(trade.correction, None);
+    assert_eq!(trade.exchange, 11);
+    assert_eq!(trade.id, "1");
+    assert_eq!(
+      trade.participant_timestamp,
+      Some(DateTime::parse_from_rfc3339("2018-02-02T09:00:00.015577Z").unwrap().into())
+    );
+    assert_eq!(trade.price, Num::new(17155, 100));
+    assert_eq!(trade.sequence_number, 1063);
+    assert_eq!(
+      trade.sip_timestamp,
+      DateTime::parse_from_rfc3339("2018-02-02T09:00:00.016036581Z").unwrap()
+    );
+    assert_eq!(trade.size, 100);
+    assert_eq!(trade.tape, Some(3));
+    assert_eq!(trade.trf_id, None);
+    assert_eq!(trade.trf_timestamp, None);
+  }
+
+  #[cfg(not(target_arch = "wasm32"))]
+  #[test(tokio::test)]
+  async fn request_aapl_trades() {
+    let client = Client::from_env().unwrap();
+    let start = DateTime::parse_from_rfc3339("2021-11-01T09:30:00-04:00").unwrap();
+    let end = DateTime::parse_from_rfc3339("2021-11-01T09:30:01-04:00").unwrap();
+    let request = TradeReq {
+      symbol: "AAPL".into(),
+      timestamp: vec![
+        Filter::GreaterThanOrEqual(start.into()),
+        Filter::LessThan(end.into()),
+      ],
+      order: Some(Order::Ascending),
+      sort: Some(Sort::Timestamp),
+      limit: Some(50),
+      cursor: None,
+    };
+
+    // There are thousands of trades in the time span. Only retrieve the
+    // first two pages to not run into rate limits.
+    let trades = client
+      .pages::<Get>(request)
+      .take(2)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert_eq!(trades.len(), 100);
+    assert!(trades.windows(2).all(|trades| trades[0].sip_timestamp <= trades[1].sip_timestamp));
+    assert!(trades.iter().all(|trade| trade.sip_timestamp >= start));
+    assert!(trades.iter().all(|trade| trade.sip_timestamp < end));
+  }
+}
@@ -4,8 +4,12 @@
 use std::borrow::Cow;
 use std::collections::HashSet;
 use std::fmt::Debug;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
 
 #[cfg(not(target_arch = "wasm32"))]
+use futures::future::Either;
+use futures::stream::unfold;
 use futures::Stream;
 
 use http_endpoint::Endpoint;
@@ -17,7 +21,6 @@ use tracing::trace;
 use tracing::Level;
 use tracing_futures::Instrument;
 
-#[cfg(not(target_arch = "wasm32"))]
 use serde_json::Error as JsonError;
 
 use url::Url;
@@ -25,16 +28,18 @@ use url::Url;
 #[cfg(not(target_arch = "wasm32"))]
 use websocket_util::tungstenite::Error as WebSocketError;
 
+use crate::api::Paginated;
 use crate::api_info::ApiInfo;
 use crate::error::Error;
 use crate::error::RequestError;
+use crate::events::stream;
+use crate::events::Event;
 use crate::events::Stock;
 use crate::events::Subscription;
+#[cfg(target_arch = "wasm32")]
+use crate::events::WebSocketError;
 #[cfg(not(target_arch = "wasm32"))]
-use crate::events::{
-  stream,
-  Event,
-};
+use crate::sandbox;
 
 /// The query parameter used for communicating the API key to Polygon.
 const API_KEY_PARAM: &str = "apiKey";
@@ -280,13 +285,48 @@ use wasm::*;
 pub struct Client {
   api_info: ApiInfo,
   client: Backend,
+  /// The directory containing fixtures to answer requests from, if the
+  /// client operates in sandbox mode.
+  #[cfg(not(target_arch = "wasm32"))]
+  sandbox: Option<PathBuf>,
 }
 
 impl Client {
   /// Create a new `Client` using the given API information.
   pub fn new(api_info: ApiInfo) -> Self {
     let client = new();
-    Self { api_info, client }
+    Self {
+      api_info,
+      client,
+      #[cfg(not(target_arch = "wasm32"))]
+      sandbox: None,
+    }
+  }
+
+  /// Create a new `Client` operating in sandbox mode, answering all
+  /// requests from fixtures in the given directory instead of
+  /// contacting Polygon.
+  ///
+  /// A request to an endpoint, say, `/v2/reference/markets` is
+  /// answered with the contents of `<dir>/v2/reference/markets.json`.
+  /// For requests with a query, a fixture named
+  /// `<dir>/<path>@<query>.json` takes precedence. Fixtures are
+  /// evaluated just like actual responses, so their contents are
+  /// parsed and mapped to errors exactly as in production. They are
+  /// evaluated as having HTTP status 200, unless the status is part of
+  /// the file name, as in `<dir>/<path>.404.json`. If no fixture is
+  /// present a `RequestError::MissingFixture` error is reported.
+  ///
+  /// Subscriptions are served from `<dir>/stocks.jsonl`, with each
+  /// line containing a message as sent by Polygon.
+  #[cfg(not(target_arch = "wasm32"))]
+  pub fn sandbox<P>(dir: P) -> Self
+  where
+    P: Into<PathBuf>,
+  {
+    let mut client = Self::new(ApiInfo::new(""));
+    client.sandbox = Some(dir.into());
+    client
   }
 
   /// Create a new `Client` with information from the environment.
@@ -301,11 +341,48 @@ impl Client {
   where
     E: Endpoint,
   {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+      if let Some(dir) = &self.sandbox {
+        return sandbox::issue::<E>(dir, input).await
+      }
+    }
+
     issue::<E>(&self.client, &self.api_info, input).await
   }
 
+  /// Issue requests to a paginated endpoint, retrieving one page after
+  /// the other.
+  ///
+  /// The returned stream reports the results of each page. It ends
+  /// once the last page was retrieved or after the first error.
+  pub fn pages<'slf, E>(
+    &'slf self,
+    input: E::Input,
+  ) -> impl Stream<Item = Result<Vec<E::Item>, RequestError<E::Error>>> + 'slf
+  where
+    E: Paginated + 'slf,
+    E::Input: Clone + 'slf,
+  {
+    unfold(Some(input), move |input| async move {
+      let mut input = input?;
+      match self.issue::<E>(input.clone()).await {
+        Ok(page) => {
+          let next = page.cursor.map(|cursor| {
+            E::set_cursor(&mut input, cursor);
+            input
+          });
+          Some((Ok(page.results), next))
+        },
+        Err(err) => Some((Err(err), None)),
+      }
+    })
+  }
+
   /// Subscribe to the given stream in order to receive updates.
-  #[cfg(not(target_arch = "wasm32"))]
+  ///
+  /// When running inside a browser, the returned stream is backed by
+  /// the `WebSocket` API provided by it.
   pub async fn subscribe<S>(
     &self,
     subscriptions: S,
@@ -318,7 +395,6 @@ impl Client {
   }
 
   /// Implementation of `subscribe` that creates a proper span.
-  #[cfg(not(target_arch = "wasm32"))]
   #[instrument(level = "debug", skip(self, subscriptions))]
   async fn subscribe_<S>(
     &self,
@@ -327,6 +403,13 @@ impl Client {
   where
     S: IntoIterator<Item = Subscription> + Debug,
   {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+      if let Some(dir) = &self.sandbox {
+        return sandbox::stream(dir, subscriptions).await.map(Either::Left)
+      }
+    }
+
     let mut url = self.api_info.stream_url.clone();
     url.set_scheme("wss").map_err(|()| {
       Error::Str(format!("unable to change URL scheme for {}: invalid URL?", url).into())
@@ -339,7 +422,10 @@ impl Client {
       api_key: self.api_info.api_key.clone(),
     };
 
-    stream(api_info, subscriptions).await
+    let stream = stream(api_info, subscriptions).await?;
+    #[cfg(not(target_arch = "wasm32"))]
+    let stream = Either::Right(stream);
+    Ok(stream)
   }
 }
 
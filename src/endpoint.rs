@@ -8,6 +8,10 @@ use std::fmt::Result as FmtResult;
 
 use serde::Deserialize;
 
+use url::form_urlencoded::Serializer;
+
+use crate::Str;
+
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct ErrorMessage {
@@ -25,6 +29,17 @@ impl Display for ErrorMessage {
 impl Error for ErrorMessage {}
 
 
+/// Finish the construction of a query, mapping an empty one to `None`.
+pub(crate) fn finish_query(mut query: Serializer<'_, String>) -> Option<Str> {
+  let query = query.finish();
+  if query.is_empty() {
+    None
+  } else {
+    Some(query.into())
+  }
+}
+
+
 /// A macro used for defining the properties for a request to a
 /// particular HTTP endpoint.
 macro_rules! Endpoint {
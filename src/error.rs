@@ -4,6 +4,8 @@
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Error as IoError;
 use std::str::from_utf8;
 #[cfg(target_arch = "wasm32")]
 use std::string::FromUtf8Error;
@@ -24,6 +26,8 @@ use wasm_bindgen::JsValue;
 #[cfg(not(target_arch = "wasm32"))]
 use websocket_util::tungstenite::Error as WebSocketError;
 
+#[cfg(target_arch = "wasm32")]
+use crate::events::WebSocketError;
 use crate::Str;
 
 
@@ -41,6 +45,18 @@ pub enum RequestError<E> {
     #[source]
     HyperError,
   ),
+  /// No fixture was found for a request issued by a sandboxed client.
+  #[cfg(not(target_arch = "wasm32"))]
+  #[error("no fixture found for request to {0}")]
+  MissingFixture(String),
+  /// An I/O error, e.g., when reading a fixture.
+  #[cfg(not(target_arch = "wasm32"))]
+  #[error("encountered an I/O error")]
+  Io(
+    #[from]
+    #[source]
+    IoError,
+  ),
   /// A UTF-8 error that may occur when converting bytes to a string.
   #[cfg(target_arch = "wasm32")]
   #[error("a UTF-8 conversion failed")]
@@ -102,6 +118,14 @@ pub enum Error {
   /// supported.
   #[error("encountered an unexpected HTTP status: {0}")]
   HttpStatus(HttpStatusCode, #[source] HttpBody),
+  /// An I/O error.
+  #[cfg(not(target_arch = "wasm32"))]
+  #[error("encountered an I/O error")]
+  Io(
+    #[from]
+    #[source]
+    IoError,
+  ),
   /// A JSON conversion error.
   #[error("a JSON conversion failed")]
   Json(
@@ -120,7 +144,6 @@ pub enum Error {
     ParseError,
   ),
   /// A websocket error.
-  #[cfg(not(target_arch = "wasm32"))]
   #[error("encountered a websocket related error")]
   WebSocket(
     #[from]
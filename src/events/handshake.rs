@@ -1,4 +1,4 @@
-// Copyright (C) 2019-2021 Daniel Mueller <deso@posteo.net>
+// Copyright (C) 2019-2022 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use futures::Sink;
@@ -16,10 +16,16 @@ use serde::Serialize;
 use serde_json::from_slice as from_json;
 use serde_json::to_string as to_json;
 
+#[cfg(not(target_arch = "wasm32"))]
 use websocket_util::tungstenite::Error as WebSocketError;
+#[cfg(not(target_arch = "wasm32"))]
 use websocket_util::tungstenite::Message as WebSocketMsg;
 
 use crate::Error;
+#[cfg(target_arch = "wasm32")]
+use crate::events::wasm::Message as WebSocketMsg;
+#[cfg(target_arch = "wasm32")]
+use crate::events::wasm::WebSocketError;
 use crate::events::stream::Code;
 use crate::events::stream::Message;
 use crate::events::stream::Messages;
@@ -174,11 +180,16 @@ where
     count = match msg {
       WebSocketMsg::Text(text) => check_responses(text.as_bytes(), expected, count, operation)?,
       WebSocketMsg::Binary(data) => check_responses(data.as_slice(), expected, count, operation)?,
+      // Note that when running inside a browser, control frames are
+      // handled transparently and never reach us.
+      #[cfg(not(target_arch = "wasm32"))]
       WebSocketMsg::Ping(dat) => {
         stream.send(WebSocketMsg::Pong(dat)).await?;
         count
       },
+      #[cfg(not(target_arch = "wasm32"))]
       WebSocketMsg::Pong(..) => count,
+      #[cfg(not(target_arch = "wasm32"))]
       WebSocketMsg::Close(..) => {
         return Err(Error::Str(
           "websocket connection closed unexpectedly".into(),
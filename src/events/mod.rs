@@ -1,13 +1,17 @@
-// Copyright (C) 2020 Daniel Mueller <deso@posteo.net>
+// Copyright (C) 2020-2022 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-#[cfg(not(target_arch = "wasm32"))]
 mod handshake;
-#[cfg(not(target_arch = "wasm32"))]
 mod stream;
 mod subscription;
+// The browser independent parts of the module are tested natively as
+// well.
+#[cfg(any(target_arch = "wasm32", test))]
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+mod wasm;
 
 #[cfg(not(target_arch = "wasm32"))]
+pub(crate) use stream::events;
 pub use stream::{
   stream,
   Aggregate,
@@ -17,3 +21,5 @@ pub use stream::{
 };
 pub use subscription::Stock;
 pub use subscription::Subscription;
+#[cfg(target_arch = "wasm32")]
+pub use wasm::WebSocketError;
@@ -17,18 +17,29 @@ use serde_json::from_str as from_json_str;
 use serde_json::Error as JsonError;
 
 use tracing::debug;
+#[cfg(not(target_arch = "wasm32"))]
 use tracing::trace;
 
+#[cfg(not(target_arch = "wasm32"))]
 use tungstenite::connect_async;
 
+#[cfg(not(target_arch = "wasm32"))]
 use websocket_util::tungstenite::Error as WebSocketError;
+#[cfg(not(target_arch = "wasm32"))]
 use websocket_util::wrap::Message as WebSocketMessage;
+#[cfg(not(target_arch = "wasm32"))]
 use websocket_util::wrap::Wrapper;
 
 use crate::api_info::ApiInfo;
 use crate::error::Error;
 use crate::events::handshake::handshake;
 use crate::events::subscription::Subscription;
+#[cfg(target_arch = "wasm32")]
+use crate::events::wasm::Message as WebSocketMessage;
+#[cfg(target_arch = "wasm32")]
+use crate::events::wasm::WebSocket;
+#[cfg(target_arch = "wasm32")]
+use crate::events::wasm::WebSocketError;
 
 
 /// A data point for a trade.
@@ -49,6 +60,12 @@ pub struct Trade {
   /// The trade's timestamp.
   #[serde(rename = "t", deserialize_with = "datetime_from_timestamp")]
   pub timestamp: DateTime<Utc>,
+  /// The conditions of the trade.
+  ///
+  /// The meaning of the codes can be looked up using the
+  /// `api::conditions` module.
+  #[serde(rename = "c", default)]
+  pub conditions: Vec<u64>,
 }
 
 
@@ -79,6 +96,12 @@ pub struct Quote {
   /// The quote's timestamp.
   #[serde(rename = "t", deserialize_with = "datetime_from_timestamp")]
   pub timestamp: DateTime<Utc>,
+  /// The condition of the quote, if any.
+  ///
+  /// The meaning of the code can be looked up using the
+  /// `api::conditions` module.
+  #[serde(rename = "c")]
+  pub condition: Option<u64>,
 }
 
 
@@ -210,7 +233,7 @@ impl Event {
     }
   }
 
-  #[cfg(test)]
+  #[cfg(all(test, not(target_arch = "wasm32")))]
   fn to_trade(&self) -> Option<&Trade> {
     match self {
       Event::Trade(trade) => Some(trade),
@@ -218,7 +241,7 @@ impl Event {
     }
   }
 
-  #[cfg(test)]
+  #[cfg(all(test, not(target_arch = "wasm32")))]
   fn to_quote(&self) -> Option<&Quote> {
     match self {
       Event::Quote(quote) => Some(quote),
@@ -306,7 +329,30 @@ where
 }
 
 
+/// Convert a stream of websocket messages into a stream of events.
+pub(crate) fn events<S>(stream: S) -> impl Stream<Item = Result<Result<Event, JsonError>, WebSocketError>>
+where
+  S: Stream<Item = Result<WebSocketMessage, WebSocketError>>,
+{
+  let stream = stream.map(|result| {
+    result.map(|message| match message {
+      WebSocketMessage::Text(string) => from_json_str::<Messages>(&string),
+      WebSocketMessage::Binary(data) => from_json_slice::<Messages>(&data),
+    })
+  });
+  let stream = Box::pin(stream);
+  unfold(
+    (false, (stream, Vec::new())),
+    |(mut stop, (mut stream, mut messages))| async move {
+      let result = handle_msg(&mut stop, &mut stream, &mut messages).await;
+      result.map(|result| (result, (stop, (stream, messages))))
+    },
+  )
+}
+
+
 /// Subscribe to and stream events from the Polygon service.
+#[cfg(not(target_arch = "wasm32"))]
 #[allow(clippy::cognitive_complexity)]
 pub async fn stream<S>(
   api_info: ApiInfo,
@@ -330,25 +376,42 @@ where
   handshake(&mut stream, api_key, subscriptions).await?;
   debug!("subscription successful");
 
-  let stream = Wrapper::builder().build(stream).map(|result| {
-    result.map(|message| match message {
-      WebSocketMessage::Text(string) => from_json_str::<Messages>(&string),
-      WebSocketMessage::Binary(data) => from_json_slice::<Messages>(&data),
-    })
-  });
-  let stream = Box::pin(stream);
-  let stream = unfold(
-    (false, (stream, Vec::new())),
-    |(mut stop, (mut stream, mut messages))| async move {
-      let result = handle_msg(&mut stop, &mut stream, &mut messages).await;
-      result.map(|result| (result, (stop, (stream, messages))))
-    },
-  );
+  let stream = Wrapper::builder().build(stream);
+  Ok(events(stream))
+}
+
+
+/// Subscribe to and stream events from the Polygon service.
+///
+/// When running inside a browser the connection is established using
+/// the `WebSocket` API it provides.
+#[cfg(target_arch = "wasm32")]
+pub async fn stream<S>(
+  api_info: ApiInfo,
+  subscriptions: S,
+) -> Result<impl Stream<Item = Result<Result<Event, JsonError>, WebSocketError>>, Error>
+where
+  S: IntoIterator<Item = Subscription>,
+{
+  let ApiInfo {
+    stream_url: url,
+    api_key,
+    ..
+  } = api_info;
+
+  debug!(message = "connecting", url = display(&url));
 
-  Ok(stream)
+  let mut stream = WebSocket::connect(&url).await?;
+  debug!("connection successful");
+
+  handshake(&mut stream, api_key, subscriptions).await?;
+  debug!("subscription successful");
+
+  Ok(events(stream))
 }
 
 
+#[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -375,8 +438,6 @@ mod tests {
   use websocket_util::test::WebSocketStream;
 
   use crate::events::subscription::Stock;
-
-  #[cfg(not(target_arch = "wasm32"))]
   use crate::Client;
 
   const API_KEY: &str = "USER12345678";
@@ -433,7 +494,7 @@ mod tests {
       "x": 19,
       "p": 293.67,
       "s": 100,
-      "c": [],
+      "c": [14, 41],
       "t": 1583527402638,
       "z": 2
     }"#;
@@ -446,6 +507,7 @@ mod tests {
       trade.timestamp,
       DateTime::parse_from_rfc3339("2020-03-06T15:43:22.638-05:00").unwrap()
     );
+    assert_eq!(trade.conditions, vec![14, 41]);
   }
 
   /// Check that we can deserialize a `Quote`.
@@ -476,6 +538,7 @@ mod tests {
       quote.timestamp,
       DateTime::parse_from_rfc3339("2020-03-06T15:36:44.684-05:00").unwrap()
     );
+    assert_eq!(quote.condition, Some(0));
   }
 
   /// Check that we can deserialize an `Aggregate`.
@@ -724,7 +787,6 @@ mod tests {
   }
 
   /// Check that we can stream realtime market data quotes.
-  #[cfg(not(target_arch = "wasm32"))]
   #[test(tokio::test)]
   #[ignore = "requires paid subscription"]
   async fn stream_market_data_updates() {
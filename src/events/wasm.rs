@@ -0,0 +1,417 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+#[cfg(target_arch = "wasm32")]
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+#[cfg(target_arch = "wasm32")]
+use std::pin::Pin;
+#[cfg(target_arch = "wasm32")]
+use std::rc::Rc;
+#[cfg(target_arch = "wasm32")]
+use std::task::Context;
+#[cfg(target_arch = "wasm32")]
+use std::task::Poll;
+
+use futures::channel::mpsc::unbounded;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::channel::mpsc::UnboundedSender;
+use futures::channel::oneshot;
+#[cfg(target_arch = "wasm32")]
+use futures::Sink;
+#[cfg(target_arch = "wasm32")]
+use futures::Stream;
+
+#[cfg(target_arch = "wasm32")]
+use js_sys::ArrayBuffer;
+#[cfg(target_arch = "wasm32")]
+use js_sys::Uint8Array;
+
+use thiserror::Error as ThisError;
+
+#[cfg(target_arch = "wasm32")]
+use url::Url;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::closure::Closure;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsValue;
+
+#[cfg(target_arch = "wasm32")]
+use web_sys::BinaryType;
+#[cfg(target_arch = "wasm32")]
+use web_sys::CloseEvent;
+#[cfg(target_arch = "wasm32")]
+use web_sys::Event;
+#[cfg(target_arch = "wasm32")]
+use web_sys::MessageEvent;
+#[cfg(target_arch = "wasm32")]
+use web_sys::WebSocket as WebSys;
+
+
+/// An error reported by a browser provided websocket.
+#[derive(Clone, Debug, ThisError)]
+pub enum WebSocketError {
+  /// The websocket connection has already been closed.
+  #[error("the websocket connection is already closed")]
+  AlreadyClosed,
+  /// A JavaScript reported error.
+  // Similar to `RequestError::JavaScript`, we cannot store the
+  // `JsValue` directly because it does not implement `Send`.
+  #[error("a JavaScript error occurred: {0}")]
+  JavaScript(String),
+}
+
+#[cfg(target_arch = "wasm32")]
+impl From<JsValue> for WebSocketError {
+  fn from(e: JsValue) -> Self {
+    match e.as_string() {
+      Some(s) => Self::JavaScript(s),
+      None => Self::JavaScript(format!("{:?}", e)),
+    }
+  }
+}
+
+
+/// A message sent or received over a browser provided websocket.
+///
+/// In contrast to a native websocket, control frames such as pings are
+/// handled by the browser and never surface here.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Message {
+  /// A text message.
+  Text(String),
+  /// A binary message.
+  Binary(Vec<u8>),
+}
+
+impl Message {
+  /// Create a new text message.
+  pub fn text<S>(string: S) -> Self
+  where
+    S: Into<String>,
+  {
+    Self::Text(string.into())
+  }
+}
+
+impl Display for Message {
+  fn fmt(&self, fmt: &mut Formatter<'_>) -> FmtResult {
+    match self {
+      Self::Text(string) => fmt.write_str(string),
+      Self::Binary(data) => write!(fmt, "Binary Data<length={}>", data.len()),
+    }
+  }
+}
+
+
+/// The receiving end of the channel reporting the outcome of a
+/// connection attempt.
+type Open = oneshot::Receiver<Result<(), WebSocketError>>;
+/// The receiving end of the channel conveying received messages.
+type Messages = UnboundedReceiver<Result<Message, WebSocketError>>;
+
+
+/// The state shared by the callbacks we register with the browser's
+/// `WebSocket`.
+///
+/// The type is free of any browser specifics, so that our handling of
+/// the various websocket events can be tested natively.
+#[derive(Debug)]
+struct State {
+  /// The sender for reporting the outcome of the connection attempt,
+  /// if it is still pending.
+  opened: Option<oneshot::Sender<Result<(), WebSocketError>>>,
+  /// The sender for forwarding received messages and errors.
+  messages: UnboundedSender<Result<Message, WebSocketError>>,
+}
+
+impl State {
+  /// Create a new `State` object along with the receiving ends of the
+  /// channels it feeds.
+  fn new() -> (Self, Open, Messages) {
+    let (opened, open) = oneshot::channel();
+    let (sender, messages) = unbounded();
+    let state = Self {
+      opened: Some(opened),
+      messages: sender,
+    };
+    (state, open, messages)
+  }
+
+  /// Handle the establishment of the connection.
+  fn on_open(&mut self) {
+    if let Some(opened) = self.opened.take() {
+      let _ = opened.send(Ok(()));
+    }
+  }
+
+  /// Handle the receipt of a message.
+  fn on_message(&mut self, message: Result<Message, WebSocketError>) {
+    let _ = self.messages.unbounded_send(message);
+  }
+
+  /// Handle an error reported by the websocket.
+  fn on_error(&mut self) {
+    // Browsers deliberately do not provide any details about the
+    // error.
+    let err = WebSocketError::JavaScript("the websocket reported an error".into());
+    let _ = self.messages.unbounded_send(Err(err));
+  }
+
+  /// Handle the closing of the connection.
+  fn on_close(&mut self, code: u16, reason: &str) {
+    // If we never managed to connect report the failure to the
+    // connecting party.
+    if let Some(opened) = self.opened.take() {
+      let err = WebSocketError::JavaScript(format!(
+        "failed to connect websocket: {} ({})",
+        reason, code
+      ));
+      let _ = opened.send(Err(err));
+    }
+    self.messages.close_channel();
+  }
+}
+
+
+/// Convert the payload of a `MessageEvent` into a `Message`.
+#[cfg(target_arch = "wasm32")]
+fn message_from_event(event: &MessageEvent) -> Result<Message, WebSocketError> {
+  let data = event.data();
+  if let Some(string) = data.as_string() {
+    Ok(Message::Text(string))
+  } else if let Some(buffer) = data.dyn_ref::<ArrayBuffer>() {
+    Ok(Message::Binary(Uint8Array::new(buffer).to_vec()))
+  } else {
+    Err(WebSocketError::JavaScript(format!(
+      "received unsupported websocket message: {:?}",
+      data
+    )))
+  }
+}
+
+
+/// A `Stream` and `Sink` of `Message` objects backed by the browser's
+/// `WebSocket` API.
+///
+/// The stream ends once the connection got closed.
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+pub(crate) struct WebSocket {
+  socket: WebSys,
+  messages: Messages,
+  // The various callbacks need to be kept alive for as long as the
+  // socket may invoke them.
+  _on_open: Closure<dyn FnMut(Event)>,
+  _on_message: Closure<dyn FnMut(MessageEvent)>,
+  _on_error: Closure<dyn FnMut(Event)>,
+  _on_close: Closure<dyn FnMut(CloseEvent)>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl WebSocket {
+  /// Connect to the websocket server at the given URL, waiting for the
+  /// connection to be established.
+  pub async fn connect(url: &Url) -> Result<Self, WebSocketError> {
+    let socket = WebSys::new(url.as_str())?;
+    socket.set_binary_type(BinaryType::Arraybuffer);
+
+    let (state, open, messages) = State::new();
+    let state = Rc::new(RefCell::new(state));
+
+    let on_open = {
+      let state = state.clone();
+      Closure::wrap(Box::new(move |_: Event| {
+        state.borrow_mut().on_open();
+      }) as Box<dyn FnMut(Event)>)
+    };
+
+    let on_message = {
+      let state = state.clone();
+      Closure::wrap(Box::new(move |event: MessageEvent| {
+        state.borrow_mut().on_message(message_from_event(&event));
+      }) as Box<dyn FnMut(MessageEvent)>)
+    };
+
+    let on_error = {
+      let state = state.clone();
+      Closure::wrap(Box::new(move |_: Event| {
+        state.borrow_mut().on_error();
+      }) as Box<dyn FnMut(Event)>)
+    };
+
+    let on_close = Closure::wrap(Box::new(move |event: CloseEvent| {
+      state.borrow_mut().on_close(event.code(), &event.reason());
+    }) as Box<dyn FnMut(CloseEvent)>);
+
+    socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    let socket = Self {
+      socket,
+      messages,
+      _on_open: on_open,
+      _on_message: on_message,
+      _on_error: on_error,
+      _on_close: on_close,
+    };
+
+    open.await.map_err(|_| WebSocketError::AlreadyClosed)??;
+    Ok(socket)
+  }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Stream for WebSocket {
+  type Item = Result<Message, WebSocketError>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    Pin::new(&mut self.messages).poll_next(cx)
+  }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Sink<Message> for WebSocket {
+  type Error = WebSocketError;
+
+  fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn start_send(self: Pin<&mut Self>, message: Message) -> Result<(), Self::Error> {
+    let result = match message {
+      Message::Text(string) => self.socket.send_with_str(&string),
+      Message::Binary(data) => self.socket.send_with_u8_array(&data),
+    };
+    result.map_err(WebSocketError::from)
+  }
+
+  fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    // The browser buffers data internally and does not provide a way
+    // for us to wait for it to be sent.
+    Poll::Ready(Ok(()))
+  }
+
+  fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(self.socket.close().map_err(WebSocketError::from))
+  }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Drop for WebSocket {
+  fn drop(&mut self) {
+    // Make sure that the socket won't invoke any of our callbacks after
+    // they got destroyed.
+    self.socket.set_onopen(None);
+    self.socket.set_onmessage(None);
+    self.socket.set_onerror(None);
+    self.socket.set_onclose(None);
+    let _ = self.socket.close();
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use futures::channel::mpsc::TryRecvError;
+  use futures::FutureExt as _;
+  use futures::StreamExt as _;
+
+
+  /// Check that we can format messages as expected.
+  #[test]
+  fn display_messages() {
+    assert_eq!(Message::text("foobar").to_string(), "foobar");
+    assert_eq!(
+      Message::Binary(vec![1, 2, 3]).to_string(),
+      "Binary Data<length=3>"
+    );
+  }
+
+  /// Check that messages are forwarded once the connection is
+  /// established and that the stream ends when it is closed.
+  #[test]
+  fn forward_messages() {
+    let (mut state, open, messages) = State::new();
+    state.on_open();
+    assert!(matches!(open.now_or_never(), Some(Ok(Ok(())))));
+
+    state.on_message(Ok(Message::text("foo")));
+    state.on_message(Ok(Message::Binary(vec![42])));
+    state.on_close(1000, "");
+
+    let messages = messages
+      .collect::<Vec<_>>()
+      .now_or_never()
+      .unwrap()
+      .into_iter()
+      .map(Result::unwrap)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      messages,
+      vec![Message::text("foo"), Message::Binary(vec![42])]
+    );
+  }
+
+  /// Check that errors reported by the websocket are forwarded without
+  /// ending the stream.
+  #[test]
+  fn forward_errors() {
+    let (mut state, _open, mut messages) = State::new();
+    state.on_open();
+    state.on_error();
+
+    let result = messages.try_recv().unwrap();
+    match result {
+      Err(WebSocketError::JavaScript(err)) => {
+        assert_eq!(err, "the websocket reported an error")
+      },
+      _ => panic!("unexpected result: {:?}", result),
+    }
+    // No message is available, but the stream has not ended either.
+    assert!(matches!(messages.try_recv(), Err(TryRecvError::Empty)));
+  }
+
+  /// Check that a connection closed before being established is
+  /// reported as a connection failure.
+  #[test]
+  fn report_connect_failure() {
+    let (mut state, open, mut messages) = State::new();
+    state.on_error();
+    state.on_close(1006, "unreachable");
+
+    let result = open.now_or_never().unwrap().unwrap();
+    match result {
+      Err(WebSocketError::JavaScript(err)) => {
+        assert_eq!(err, "failed to connect websocket: unreachable (1006)")
+      },
+      _ => panic!("unexpected result: {:?}", result),
+    }
+
+    assert!(matches!(messages.try_recv(), Ok(Err(..))));
+    assert!(matches!(messages.try_recv(), Err(TryRecvError::Closed)));
+  }
+
+  /// Check that events after the connection got closed are ignored.
+  #[test]
+  fn ignore_events_after_close() {
+    let (mut state, open, mut messages) = State::new();
+    state.on_open();
+    state.on_close(1000, "");
+    state.on_open();
+    state.on_message(Ok(Message::text("foo")));
+    state.on_error();
+
+    assert!(matches!(open.now_or_never(), Some(Ok(Ok(())))));
+    assert!(matches!(messages.try_recv(), Err(TryRecvError::Closed)));
+  }
+}
@@ -0,0 +1,319 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::error::Error as StdError;
+use std::future::Future;
+use std::rc::Rc;
+use std::str::FromStr as _;
+
+use chrono::NaiveDate;
+use chrono::TimeZone as _;
+use chrono::Utc;
+
+use js_sys::Error as JsError;
+use js_sys::JSON::parse;
+
+use serde::Serialize;
+use serde_json::to_string as to_json;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsCast as _;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::future_to_promise;
+
+use crate::api::aggregates;
+use crate::api::market_status;
+use crate::api::ticker;
+use crate::ApiInfo;
+use crate::Client;
+
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPESCRIPT_TYPES: &str = r#"
+/** An aggregate as reported by `PolygonClient.aggregates`. */
+export interface Aggregate {
+  /** The aggregate's timestamp, as RFC 3339 string. */
+  timestamp: string;
+  volume: number;
+  // Prices that cannot be represented as a `number` are `null`.
+  open: number | null;
+  close: number | null;
+  high: number | null;
+  low: number | null;
+}
+
+/** The market status as reported by `PolygonClient.marketStatus`. */
+export interface MarketStatus {
+  status: "open" | "closed" | "extended-hours" | "unknown";
+  earlyHours: boolean;
+  afterHours: boolean;
+  /** The current server time, as RFC 3339 string. */
+  serverTime: string;
+}
+
+/** A ticker as reported by `PolygonClient.ticker`. */
+export interface Ticker {
+  ticker: string;
+  name: string;
+  market: "STOCKS" | "INDEX" | "FX" | "CRYPTO" | "OTC";
+  locale: string;
+  currency: string;
+  active: boolean;
+  type: "CS" | "other" | null;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+  /// A promise resolving to a list of aggregates.
+  #[wasm_bindgen(typescript_type = "Promise<Aggregate[]>")]
+  pub type AggregatesPromise;
+
+  /// A promise resolving to the market status.
+  #[wasm_bindgen(typescript_type = "Promise<MarketStatus>")]
+  pub type MarketStatusPromise;
+
+  /// A promise resolving to a ticker.
+  #[wasm_bindgen(typescript_type = "Promise<Ticker>")]
+  pub type TickerPromise;
+}
+
+
+/// An aggregate as handed out to JavaScript.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Aggregate {
+  timestamp: String,
+  volume: f64,
+  open: Option<f64>,
+  close: Option<f64>,
+  high: Option<f64>,
+  low: Option<f64>,
+}
+
+impl From<aggregates::Aggregate> for Aggregate {
+  fn from(aggregate: aggregates::Aggregate) -> Self {
+    Self {
+      timestamp: aggregate.timestamp.to_rfc3339(),
+      volume: aggregate.volume,
+      open: aggregate.open_price.to_f64(),
+      close: aggregate.close_price.to_f64(),
+      high: aggregate.high_price.to_f64(),
+      low: aggregate.low_price.to_f64(),
+    }
+  }
+}
+
+
+/// The market status as handed out to JavaScript.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MarketStatus {
+  status: &'static str,
+  early_hours: bool,
+  after_hours: bool,
+  server_time: String,
+}
+
+impl From<market_status::Market> for MarketStatus {
+  fn from(market: market_status::Market) -> Self {
+    let status = match market.status {
+      market_status::Status::Open => "open",
+      market_status::Status::Closed => "closed",
+      market_status::Status::ExtendedHours => "extended-hours",
+      market_status::Status::Unknown => "unknown",
+    };
+
+    Self {
+      status,
+      early_hours: market.early_hours,
+      after_hours: market.after_hours,
+      server_time: market.server_time.to_rfc3339(),
+    }
+  }
+}
+
+
+/// A ticker as handed out to JavaScript.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker {
+  ticker: String,
+  name: String,
+  market: &'static str,
+  locale: String,
+  currency: String,
+  active: bool,
+  #[serde(rename = "type")]
+  type_: Option<&'static str>,
+}
+
+impl From<ticker::Ticker> for Ticker {
+  fn from(ticker: ticker::Ticker) -> Self {
+    let market = match ticker.market {
+      ticker::Market::Stocks => "STOCKS",
+      ticker::Market::Indices => "INDEX",
+      ticker::Market::ForeignExchange => "FX",
+      ticker::Market::Crypto => "CRYPTO",
+      ticker::Market::Otc => "OTC",
+    };
+    let type_ = ticker.type_.map(|type_| match type_ {
+      ticker::Type::CommonStock => "CS",
+      ticker::Type::Other => "other",
+    });
+
+    Self {
+      ticker: ticker.ticker,
+      name: ticker.name,
+      market,
+      locale: ticker.locale,
+      currency: ticker.currency,
+      active: ticker.active,
+      type_,
+    }
+  }
+}
+
+
+/// Convert an error into a JavaScript `Error` object.
+fn js_error<E>(err: E) -> JsValue
+where
+  E: StdError,
+{
+  let mut message = err.to_string();
+  let mut source = err.source();
+  while let Some(err) = source {
+    message = format!("{}: {}", message, err);
+    source = err.source();
+  }
+  JsError::new(&message).into()
+}
+
+/// Convert a serializable object into a plain JavaScript object.
+fn to_js<T>(value: &T) -> Result<JsValue, JsValue>
+where
+  T: Serialize,
+{
+  let json = to_json(value).map_err(js_error)?;
+  parse(&json)
+}
+
+/// Parse a date in `YYYY-MM-DD` format.
+fn parse_date(date: &str) -> Result<NaiveDate, JsValue> {
+  NaiveDate::from_str(date).map_err(js_error)
+}
+
+/// Convert a time span as provided by JavaScript into a `TimeSpan`.
+fn parse_time_span(time_span: &str) -> Result<aggregates::TimeSpan, JsValue> {
+  let time_span = match time_span {
+    "minute" => aggregates::TimeSpan::Minute,
+    "hour" => aggregates::TimeSpan::Hour,
+    "day" => aggregates::TimeSpan::Day,
+    "week" => aggregates::TimeSpan::Week,
+    "month" => aggregates::TimeSpan::Month,
+    "quarter" => aggregates::TimeSpan::Quarter,
+    "year" => aggregates::TimeSpan::Year,
+    _ => return Err(JsError::new(&format!("invalid time span: {}", time_span)).into()),
+  };
+  Ok(time_span)
+}
+
+/// Create a `Promise` resolving to the plain JavaScript object
+/// representation of the value produced by the given future.
+fn promise<F, T>(future: F) -> JsValue
+where
+  F: Future<Output = Result<T, JsValue>> + 'static,
+  T: Serialize,
+{
+  future_to_promise(async move { to_js(&future.await?) }).into()
+}
+
+
+/// A client for interacting with the Polygon API from JavaScript.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct PolygonClient {
+  client: Rc<Client>,
+}
+
+#[wasm_bindgen]
+impl PolygonClient {
+  /// Create a new `PolygonClient` using the given API key.
+  #[wasm_bindgen(constructor)]
+  pub fn new(api_key: String) -> Self {
+    let client = Client::new(ApiInfo::new(api_key));
+    Self {
+      client: Rc::new(client),
+    }
+  }
+
+  /// Retrieve aggregates for the given symbol.
+  ///
+  /// `time_span` is one of `minute`, `hour`, `day`, `week`, `month`,
+  /// `quarter`, and `year`. Start and end dates are inclusive and
+  /// expected to be in `YYYY-MM-DD` format.
+  pub fn aggregates(
+    &self,
+    symbol: String,
+    time_span: String,
+    multiplier: u8,
+    start: String,
+    end: String,
+  ) -> AggregatesPromise {
+    let client = self.client.clone();
+    promise(async move {
+      let request = aggregates::AggregateReq {
+        symbol,
+        time_span: parse_time_span(&time_span)?,
+        multiplier,
+        start_date: Utc.from_utc_date(&parse_date(&start)?),
+        end_date: Utc.from_utc_date(&parse_date(&end)?),
+      };
+
+      let aggregates = client
+        .issue::<aggregates::Get>(request)
+        .await
+        .map_err(js_error)?
+        .into_result()
+        .map_err(js_error)?
+        .unwrap_or_default()
+        .into_iter()
+        .map(Aggregate::from)
+        .collect::<Vec<_>>();
+
+      Ok(aggregates)
+    })
+    .unchecked_into()
+  }
+
+  /// Retrieve the current market status.
+  #[wasm_bindgen(js_name = marketStatus)]
+  pub fn market_status(&self) -> MarketStatusPromise {
+    let client = self.client.clone();
+    promise(async move {
+      let market = client
+        .issue::<market_status::Get>(())
+        .await
+        .map_err(js_error)?;
+      Ok(MarketStatus::from(market))
+    })
+    .unchecked_into()
+  }
+
+  /// Retrieve information about the ticker with the given symbol.
+  pub fn ticker(&self, symbol: String) -> TickerPromise {
+    let client = self.client.clone();
+    promise(async move {
+      let ticker = client
+        .issue::<ticker::Get>(symbol)
+        .await
+        .map_err(js_error)?
+        .into_result()
+        .map_err(js_error)?
+        .ticker;
+      Ok(Ticker::from(ticker))
+    })
+    .unchecked_into()
+  }
+}
+
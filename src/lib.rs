@@ -67,6 +67,10 @@ pub mod events;
 mod api_info;
 mod client;
 mod error;
+#[cfg(all(target_arch = "wasm32", feature = "js"))]
+mod js;
+#[cfg(not(target_arch = "wasm32"))]
+mod sandbox;
 
 use std::borrow::Cow;
 
@@ -74,5 +78,7 @@ pub use api_info::ApiInfo;
 pub use client::Client;
 pub use error::Error;
 pub use error::RequestError;
+#[cfg(all(target_arch = "wasm32", feature = "js"))]
+pub use js::PolygonClient;
 
 type Str = Cow<'static, str>;
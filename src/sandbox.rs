@@ -0,0 +1,472 @@
+// Copyright (C) 2022 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::fs::read;
+use std::fs::read_dir;
+use std::fs::read_to_string;
+use std::io::Error as IoError;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use futures::future::ready;
+use futures::stream::iter;
+use futures::Stream;
+use futures::StreamExt as _;
+
+use http::StatusCode;
+use http_endpoint::Endpoint;
+
+use serde_json::Error as JsonError;
+
+use tracing::debug;
+
+use websocket_util::tungstenite::Error as WebSocketError;
+use websocket_util::wrap::Message as WebSocketMessage;
+
+use crate::error::Error;
+use crate::error::RequestError;
+use crate::events::events;
+use crate::events::Event;
+use crate::events::Stock;
+use crate::events::Subscription;
+
+/// The extension of fixture files for requests.
+const REQUEST_EXT: &str = "json";
+/// The name of the file containing the events to stream.
+const EVENTS_FILE: &str = "stocks.jsonl";
+
+
+/// Build the path to a fixture file, excluding the extension.
+fn fixture_base(dir: &Path, path: &str, query: Option<&str>) -> PathBuf {
+  let mut file = OsString::from(path.trim_start_matches('/'));
+  if let Some(query) = query {
+    file.push("@");
+    file.push(query);
+  }
+  dir.join(file)
+}
+
+
+/// Build the path to a fixture file.
+fn fixture_path(dir: &Path, path: &str, query: Option<&str>) -> PathBuf {
+  let mut file = fixture_base(dir, path, query).into_os_string();
+  file.push(".");
+  file.push(REQUEST_EXT);
+  PathBuf::from(file)
+}
+
+
+/// Parse the HTTP status encoded in the name of a fixture file, given
+/// the name of the fixture without status and extension.
+///
+/// A file named `<name>.<status>.json` is considered a fixture with the
+/// given status.
+fn parse_status(name: &str, file: &str) -> Option<StatusCode> {
+  let status = file
+    .strip_prefix(name)?
+    .strip_prefix('.')?
+    .strip_suffix(REQUEST_EXT)?
+    .strip_suffix('.')?;
+
+  if status.len() == 3 && status.bytes().all(|b| b.is_ascii_digit()) {
+    StatusCode::from_bytes(status.as_bytes()).ok()
+  } else {
+    None
+  }
+}
+
+
+/// Find a fixture with an explicit HTTP status for the request with
+/// the given base path.
+fn find_status_fixture(base: &Path) -> Result<Option<(StatusCode, PathBuf)>, IoError> {
+  let (parent, name) = match (base.parent(), base.file_name().and_then(OsStr::to_str)) {
+    (Some(parent), Some(name)) => (parent, name),
+    _ => return Ok(None),
+  };
+
+  let entries = match read_dir(parent) {
+    Ok(entries) => entries,
+    Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+    Err(err) => return Err(err),
+  };
+
+  let mut fixtures = Vec::new();
+  for entry in entries {
+    let entry = entry?;
+    if let Some(status) = entry.file_name().to_str().and_then(|file| parse_status(name, file)) {
+      fixtures.push((status, entry.path()))
+    }
+  }
+  // Directory entries are not reported in any particular order. Make
+  // sure that we behave deterministically should there be multiple
+  // candidates.
+  fixtures.sort();
+  Ok(fixtures.into_iter().next())
+}
+
+
+/// Read the fixture for a request to the given path and query, along
+/// with the HTTP status to evaluate it with.
+///
+/// A fixture specific to the query takes precedence over one for the
+/// path alone. For each of them, a fixture without explicit status
+/// (implying 200) takes precedence over one with.
+fn read_fixture<E>(
+  dir: &Path,
+  path: &str,
+  query: Option<&str>,
+) -> Result<(StatusCode, Vec<u8>), RequestError<E>> {
+  let mut candidates = Vec::with_capacity(2);
+  if query.is_some() {
+    candidates.push(query);
+  }
+  candidates.push(None);
+
+  for candidate in &candidates {
+    let fixture = fixture_path(dir, path, *candidate);
+    debug!(fixture = display(&fixture.display()));
+    match read(&fixture) {
+      Ok(body) => return Ok((StatusCode::OK, body)),
+      Err(err) if err.kind() == ErrorKind::NotFound => (),
+      Err(err) => return Err(RequestError::Io(err)),
+    }
+
+    let base = fixture_base(dir, path, *candidate);
+    if let Some((status, fixture)) = find_status_fixture(&base)? {
+      debug!(fixture = display(&fixture.display()), status = display(&status));
+      let body = read(&fixture)?;
+      return Ok((status, body))
+    }
+  }
+
+  let tried = candidates
+    .iter()
+    .map(|candidate| fixture_path(dir, path, *candidate).display().to_string())
+    .collect::<Vec<_>>()
+    .join(", ");
+  let request = match query {
+    Some(query) => format!("{}?{}", path, query),
+    None => path.to_string(),
+  };
+  Err(RequestError::MissingFixture(format!(
+    "{} (tried {})",
+    request, tried
+  )))
+}
+
+
+/// Answer a request to the given endpoint from a fixture.
+///
+/// The fixture for a request to, say, `/v2/reference/markets` is read
+/// from `<dir>/v2/reference/markets.json`. If the request has a query,
+/// a fixture at `<dir>/<path>@<query>.json` is preferred. The fixture's
+/// contents are evaluated just like an actual response with HTTP status
+/// 200 would be. To simulate a different status, the status can be
+/// made part of the file name, as in
+/// `<dir>/v2/reference/markets.404.json`.
+pub async fn issue<E>(dir: &Path, input: E::Input) -> Result<E::Output, RequestError<E::Error>>
+where
+  E: Endpoint,
+{
+  let path = E::path(&input);
+  let query = E::query(&input)
+    .map_err(E::Error::from)
+    .map_err(RequestError::Endpoint)?;
+
+  let (status, body) = read_fixture(dir, &path, query.as_deref())?;
+  E::evaluate(status, &body).map_err(RequestError::Endpoint)
+}
+
+
+/// Check whether an event is covered by one of the given subscriptions.
+fn matches(subscriptions: &[Subscription], event: &Event) -> bool {
+  subscriptions.iter().any(|subscription| {
+    let kind = matches!(
+      (subscription, event),
+      (Subscription::SecondAggregates(..), Event::SecondAggregate(..))
+        | (Subscription::MinuteAggregates(..), Event::MinuteAggregate(..))
+        | (Subscription::Trades(..), Event::Trade(..))
+        | (Subscription::Quotes(..), Event::Quote(..))
+    );
+
+    kind
+      && match subscription.stock() {
+        Stock::Symbol(symbol) => symbol == event.symbol(),
+        Stock::All => true,
+      }
+  })
+}
+
+
+/// Stream the events stored in `<dir>/stocks.jsonl`.
+///
+/// Each line of the file contains a message as sent by Polygon, i.e.,
+/// an array of events. Only events covered by the provided
+/// subscriptions are reported.
+pub async fn stream<S>(
+  dir: &Path,
+  subscriptions: S,
+) -> Result<impl Stream<Item = Result<Result<Event, JsonError>, WebSocketError>>, Error>
+where
+  S: IntoIterator<Item = Subscription>,
+{
+  let subscriptions = subscriptions.into_iter().collect::<Vec<_>>();
+  if subscriptions.is_empty() {
+    return Err(Error::Str(
+      "failed to subscribe to event stream: no subscriptions supplied".into(),
+    ))
+  }
+
+  let path = dir.join(EVENTS_FILE);
+  debug!(fixture = display(&path.display()));
+
+  let content = read_to_string(&path).map_err(|err| {
+    if err.kind() == ErrorKind::NotFound {
+      Error::Str(format!("no event fixture found at {}", path.display()).into())
+    } else {
+      Error::Io(err)
+    }
+  })?;
+
+  let messages = content
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| WebSocketMessage::Text(line.to_string()))
+    .collect::<Vec<_>>();
+
+  let stream = events(iter(messages).map(Ok)).filter(move |result| {
+    let keep = match result {
+      Ok(Ok(event)) => matches(&subscriptions, event),
+      _ => true,
+    };
+    ready(keep)
+  });
+  Ok(stream)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::env::temp_dir;
+  use std::fs::create_dir_all;
+  use std::fs::remove_dir_all;
+  use std::fs::write;
+  use std::process::id;
+
+  use futures::TryStreamExt as _;
+
+  use test_log::test;
+
+  use crate::api::markets;
+  use crate::api::ticker;
+  use crate::api::trades;
+  use crate::Client;
+
+
+  /// A temporary directory that is removed when dropped.
+  struct TempDir(PathBuf);
+
+  impl TempDir {
+    fn new(name: &str) -> Self {
+      let dir = temp_dir().join(format!("polyio-{}-{}", name, id()));
+      create_dir_all(&dir).unwrap();
+      Self(dir)
+    }
+
+    fn write(&self, path: &str, content: &str) {
+      let path = self.0.join(path);
+      create_dir_all(path.parent().unwrap()).unwrap();
+      write(path, content).unwrap();
+    }
+  }
+
+  impl Drop for TempDir {
+    fn drop(&mut self) {
+      let _ = remove_dir_all(&self.0);
+    }
+  }
+
+
+  /// Check that we map requests to the expected fixture paths.
+  #[test]
+  fn fixture_paths() {
+    let dir = Path::new("/fixtures");
+    assert_eq!(
+      fixture_path(dir, "/v2/reference/markets", None),
+      Path::new("/fixtures/v2/reference/markets.json")
+    );
+    assert_eq!(
+      fixture_path(dir, "/v3/trades/SPY", Some("limit=10")),
+      Path::new("/fixtures/v3/trades/SPY@limit=10.json")
+    );
+  }
+
+  /// Check that we correctly parse the HTTP status from fixture file
+  /// names.
+  #[test]
+  fn parse_fixture_status() {
+    assert_eq!(
+      parse_status("markets", "markets.404.json"),
+      Some(StatusCode::NOT_FOUND)
+    );
+    assert_eq!(
+      parse_status("SPY@limit=10", "SPY@limit=10.429.json"),
+      Some(StatusCode::TOO_MANY_REQUESTS)
+    );
+    assert_eq!(parse_status("markets", "markets.json"), None);
+    assert_eq!(parse_status("markets", "markets.4040.json"), None);
+    assert_eq!(parse_status("markets", "markets.abc.json"), None);
+    assert_eq!(parse_status("markets", "locales.404.json"), None);
+    assert_eq!(parse_status("SPY", "SPY@limit=10.404.json"), None);
+  }
+
+  /// Check that requests are answered from fixtures.
+  #[test(tokio::test)]
+  async fn issue_from_fixture() {
+    let dir = TempDir::new("issue");
+    dir.write(
+      "v2/reference/markets.json",
+      r#"{"status":"OK","results":[{"market":"STOCKS","desc":"Stocks / Equities / ETFs"}]}"#,
+    );
+
+    let client = Client::sandbox(&dir.0);
+    let markets = client
+      .issue::<markets::Get>(())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+
+    assert_eq!(markets.len(), 1);
+    assert_eq!(markets[0].market, "STOCKS");
+  }
+
+  /// Check that a fixture with an explicit HTTP status is mapped to the
+  /// endpoint's error just like an actual response.
+  #[test(tokio::test)]
+  async fn issue_error_from_fixture() {
+    let dir = TempDir::new("status");
+    dir.write(
+      "v2/reference/tickers/XYZ.404.json",
+      r#"{"status":"NOT_FOUND","request_id":"2b1c6f8bd5ab87e2d4c8b9e1a2f4c3d5","message":"Ticker not found."}"#,
+    );
+
+    let client = Client::sandbox(&dir.0);
+    let result = client.issue::<ticker::Get>("XYZ".into()).await;
+    match result {
+      Err(RequestError::Endpoint(ticker::GetError::NotFound(..))) => (),
+      _ => panic!("unexpected result: {:?}", result),
+    }
+  }
+
+  /// Check that a fixture without explicit status takes precedence.
+  #[test(tokio::test)]
+  async fn issue_prefers_fixture_without_status() {
+    let dir = TempDir::new("precedence");
+    dir.write(
+      "v2/reference/markets.json",
+      r#"{"status":"OK","results":[{"market":"STOCKS","desc":"Stocks / Equities / ETFs"}]}"#,
+    );
+    dir.write(
+      "v2/reference/markets.500.json",
+      r#"{"status":"ERROR","request_id":"2b1c6f8bd5ab87e2d4c8b9e1a2f4c3d5"}"#,
+    );
+
+    let client = Client::sandbox(&dir.0);
+    let markets = client
+      .issue::<markets::Get>(())
+      .await
+      .unwrap()
+      .into_result()
+      .unwrap();
+    assert_eq!(markets.len(), 1);
+  }
+
+  /// Check that we report a missing fixture properly.
+  #[test(tokio::test)]
+  async fn issue_missing_fixture() {
+    let dir = TempDir::new("missing");
+    let client = Client::sandbox(&dir.0);
+    let result = client.issue::<markets::Get>(()).await;
+
+    match result {
+      Err(RequestError::MissingFixture(request)) => {
+        assert!(request.starts_with("/v2/reference/markets"), "{}", request)
+      },
+      _ => panic!("unexpected result: {:?}", result),
+    }
+  }
+
+  /// Check that `Client::pages` follows cursors across fixtures.
+  #[test(tokio::test)]
+  async fn paginate_fixtures() {
+    let dir = TempDir::new("pages");
+    dir.write(
+      "v3/trades/SPY@limit=1.json",
+      r#"{
+  "next_url": "https://api.polygon.io/v3/trades/SPY?cursor=bmV4dA",
+  "results": [{"exchange":4,"id":"1","price":323.1,"sequence_number":1,"sip_timestamp":1577818283019000000,"size":3}],
+  "status": "OK"
+}"#,
+    );
+    dir.write(
+      "v3/trades/SPY@cursor=bmV4dA.json",
+      r#"{
+  "results": [{"exchange":4,"id":"2","price":323.2,"sequence_number":2,"sip_timestamp":1577818283020000000,"size":1}],
+  "status": "OK"
+}"#,
+    );
+
+    let client = Client::sandbox(&dir.0);
+    let request = trades::TradeReq {
+      symbol: "SPY".into(),
+      limit: Some(1),
+      ..Default::default()
+    };
+    let trades = client
+      .pages::<trades::Get>(request)
+      .try_concat()
+      .await
+      .unwrap();
+
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0].id, "1");
+    assert_eq!(trades[1].id, "2");
+  }
+
+  /// Check that we can stream events from a fixture.
+  #[test(tokio::test)]
+  async fn stream_from_fixture() {
+    let dir = TempDir::new("stream");
+    dir.write(
+      EVENTS_FILE,
+      r#"[{"ev":"T","sym":"MSFT","i":8310,"x":4,"p":156.9799,"s":3,"c":[37],"t":1577818283019,"z":3}]
+[{"ev":"T","sym":"SPY","i":8311,"x":4,"p":323.12,"s":1,"c":[37],"t":1577818283020,"z":3}]
+[{"ev":"Q","sym":"SPY","c":1,"bx":8,"ax":12,"bp":323.1,"ap":323.14,"bs":1,"as":3,"t":1577818659363,"z":3}]
+"#,
+    );
+
+    let client = Client::sandbox(&dir.0);
+    let subscriptions = vec![Subscription::Trades(Stock::Symbol("SPY".into()))];
+    let events = client
+      .subscribe(subscriptions)
+      .await
+      .unwrap()
+      .map_err(Error::WebSocket)
+      .and_then(|result| ready(result.map_err(Error::Json)))
+      .try_collect::<Vec<_>>()
+      .await
+      .unwrap();
+
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+      Event::Trade(trade) => assert_eq!(trade.symbol, "SPY"),
+      event => panic!("unexpected event: {:?}", event),
+    }
+  }
+}